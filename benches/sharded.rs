@@ -0,0 +1,60 @@
+//! Compares the throughput of `MultiResource` and `ShardedResource` as threads increase.
+//! 
+//! Run with `cargo bench --features std --bench sharded`.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-18
+
+use bottleneck::*;
+use std::{
+  thread::{self, Thread,},
+  sync::Barrier,
+  time::{Duration, Instant,},
+};
+
+/// The number of resources in each resource pool.
+const RESOURCES: usize = 32;
+/// The number of shards in the `ShardedResource`.
+const SHARDS: usize = 8;
+/// The number of resources each thread aquires.
+const CYCLES: usize = 20_000;
+
+/// Times `threads` threads each aquiring a resource from `pool` `CYCLES` times.
+/// 
+/// # Params
+/// 
+/// pool --- The resource pool to aquire resources from.  
+/// threads --- The number of threads to aquire resources with.  
+fn run<Pool,>(pool: &Pool, threads: usize,) -> Duration
+  where Pool: ResourcePool<Resource = usize,> + Sync, {
+  let barrier = Barrier::new(threads + 1,);
+
+  thread::scope(|scope,| {
+    for _ in 0..threads {
+      scope.spawn(|| {
+        barrier.wait();
+        for _ in 0..CYCLES { pool.get_resource::<Thread, _,>(|_, r,| *r += 1,); }
+      },);
+    }
+
+    let start = Instant::now();
+
+    barrier.wait();
+    //Every thread is joined before the scope returns.
+    start
+  },).elapsed()
+}
+
+fn main() {
+  println!("{:>8} {:>14} {:>14}", "threads", "multi", "sharded",);
+
+  for &threads in [1, 4, 16, 32, 64,].iter() {
+    let multi = MultiResource::<usize,>::new_resources(RESOURCES,);
+    let sharded = ShardedResource::<usize,>::new_resources(RESOURCES, SHARDS,);
+    let ops = (threads * CYCLES) as f64;
+    let multi = ops / run(&multi, threads,).as_secs_f64();
+    let sharded = ops / run(&sharded, threads,).as_secs_f64();
+
+    println!("{:>8} {:>10.0} op/s {:>10.0} op/s", threads, multi, sharded,);
+  }
+}
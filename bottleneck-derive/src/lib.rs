@@ -0,0 +1,161 @@
+//! Derive macros for the `Resource` and `ConstResource` traits of `bottleneck`.
+//! 
+//! `#[derive(Resource)]` creates each field with `Resource::new` and
+//! `#[derive(ConstResource)]` builds `INIT` from the `INIT` of each field.
+//! 
+//! A field marked `#[resource(default)]` is created with `Default::default` instead
+//! and a struct or enum marked `#[resource(default)]` is created with its own `Default`
+//! implementation, so any `Default` type can be used as a `Resource`.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-18
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned,};
+use syn::{
+  parse_macro_input, parse_quote,
+  spanned::Spanned,
+  Attribute, Data, DeriveInput, Error, Fields, WherePredicate,
+};
+
+/// Derives `bottleneck::Resource`.
+#[proc_macro_derive(Resource, attributes(resource,),)]
+pub fn derive_resource(input: TokenStream,) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+
+  expand(input, Kind::Resource,)
+    .unwrap_or_else(Error::into_compile_error,)
+    .into()
+}
+
+/// Derives `bottleneck::ConstResource`.
+#[proc_macro_derive(ConstResource, attributes(resource,),)]
+pub fn derive_const_resource(input: TokenStream,) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+
+  expand(input, Kind::ConstResource,)
+    .unwrap_or_else(Error::into_compile_error,)
+    .into()
+}
+
+/// The trait being derived.
+#[derive(Clone, Copy, PartialEq, Eq,)]
+enum Kind {
+  /// Derives `Resource`.
+  Resource,
+  /// Derives `ConstResource`.
+  ConstResource,
+}
+
+/// Returns `true` if `attrs` contains `#[resource(default)]`.
+/// 
+/// # Params
+/// 
+/// attrs --- The attributes to search.  
+fn is_default(attrs: &[Attribute],) -> syn::Result<bool> {
+  let mut default = false;
+
+  for attr in attrs.iter().filter(|attr,| attr.path().is_ident("resource",),) {
+    attr.parse_nested_meta(|meta,| {
+      if meta.path.is_ident("default",) { default = true; Ok(()) }
+      else { Err(meta.error("expected `default`",),) }
+    },)?;
+  }
+
+  Ok(default)
+}
+
+/// Expands a derive of `kind` for `input`.
+/// 
+/// # Params
+/// 
+/// input --- The item to derive for.  
+/// kind --- The trait to derive.  
+fn expand(mut input: DeriveInput, kind: Kind,) -> syn::Result<TokenStream2> {
+  let name = input.ident.clone();
+  let span = input.span();
+
+  //The whole item is created with `Default`.
+  if is_default(&input.attrs,)? {
+    if kind == Kind::ConstResource {
+      return Err(Error::new(span, "`ConstResource` cannot be derived from `Default`",),)
+    }
+
+    let (impl_generics, ty_generics, where_clause,) = input.generics.split_for_impl();
+
+    return Ok(quote! {
+      impl #impl_generics ::bottleneck::Resource for #name #ty_generics #where_clause {
+        #[inline]
+        fn new() -> Self { ::core::default::Default::default() }
+      }
+    },)
+  }
+
+  let fields = match &input.data {
+    Data::Struct(data,) => &data.fields,
+    _ => return Err(Error::new(
+      span,
+      "`Resource` can only be derived for structs unless marked `#[resource(default)]`",
+    ),),
+  };
+  let mut bounds = Vec::<WherePredicate>::new();
+  let mut values = Vec::new();
+
+  for field in fields.iter() {
+    let ty = &field.ty;
+    let span = ty.span();
+
+    values.push(if is_default(&field.attrs,)? {
+      if kind == Kind::ConstResource {
+        return Err(Error::new(
+          field.span(), "`ConstResource` fields cannot be created with `Default`",
+        ),)
+      }
+
+      bounds.push(parse_quote!(#ty: ::core::default::Default),);
+      quote_spanned!(span=> <#ty as ::core::default::Default>::default())
+    } else if kind == Kind::Resource {
+      bounds.push(parse_quote!(#ty: ::bottleneck::Resource),);
+      quote_spanned!(span=> <#ty as ::bottleneck::Resource>::new())
+    } else {
+      bounds.push(parse_quote!(#ty: ::bottleneck::ConstResource),);
+      quote_spanned!(span=> <#ty as ::bottleneck::ConstResource>::INIT)
+    },);
+  }
+
+  let value = match fields {
+    Fields::Named(fields,) => {
+      let names = fields.named.iter().map(|field,| &field.ident,);
+
+      quote!(Self { #(#names: #values,)* })
+    },
+    Fields::Unnamed(_) => quote!(Self(#(#values,)*)),
+    Fields::Unit => quote!(Self),
+  };
+
+  //Every field type must implement the trait being used to create it.
+  {
+    let where_clause = input.generics.make_where_clause();
+
+    where_clause.predicates.extend(bounds,);
+  }
+
+  let (impl_generics, ty_generics, where_clause,) = input.generics.split_for_impl();
+
+  Ok(match kind {
+    Kind::Resource => quote! {
+      impl #impl_generics ::bottleneck::Resource for #name #ty_generics #where_clause {
+        #[inline]
+        fn new() -> Self { #value }
+      }
+    },
+    Kind::ConstResource => quote! {
+      impl #impl_generics ::bottleneck::ConstResource for #name #ty_generics #where_clause {
+        const INIT: Self = #value;
+      }
+    },
+  },)
+}
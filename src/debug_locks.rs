@@ -0,0 +1,296 @@
+//! Records which threads hold and wait on resource pools to detect deadlocks.
+//! 
+//! With the `debug-locks` feature disabled every function in this module is a no-op.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-18
+
+/// What a waiting thread is waiting for.
+#[derive(Clone, Copy,)]
+#[cfg_attr(not(any(feature = "debug-locks", feature = "tracing",),), allow(dead_code,),)]
+pub(crate) enum Wait {
+  /// Any `n` resources.
+  Any(usize,),
+  /// The resource with a specific `Id`.
+  Id(usize,),
+}
+
+/// Identifies a resource pool.
+#[derive(Clone, Copy,)]
+#[cfg_attr(not(any(feature = "debug-locks", feature = "tracing",),), allow(dead_code,),)]
+pub(crate) struct PoolId {
+  /// The address of the resource pool.
+  pub address: usize,
+  /// The name of the resource pool, or its type name if it is unnamed.
+  pub name: &'static str,
+  /// The number of resources in the resource pool.
+  pub capacity: usize,
+}
+
+impl PoolId {
+  /// Identifies a resource pool.
+  /// 
+  /// # Params
+  /// 
+  /// pool --- The resource pool.  
+  /// capacity --- The number of resources in the resource pool.  
+  #[inline]
+  pub fn of<Pool,>(pool: &Pool, capacity: usize,) -> Self {
+    Self {
+      address: pool as *const Pool as usize,
+      name: core::any::type_name::<Pool>(),
+      capacity,
+    }
+  }
+  /// Uses the name of the resource pool if it has one.
+  /// 
+  /// # Params
+  /// 
+  /// name --- The name of the resource pool.  
+  #[inline]
+  pub fn named(mut self, name: Option<&'static str>,) -> Self {
+    if let Some(name) = name { self.name = name }
+
+    self
+  }
+}
+
+#[cfg(not(feature = "debug-locks",),)]
+mod imp {
+  use super::*;
+
+  #[inline(always)]
+  pub fn aquired(_: PoolId, _: usize,) {}
+  #[inline(always)]
+  pub fn released(_: PoolId, _: usize,) {}
+  #[inline(always)]
+  pub fn waiting(_: PoolId, _: Wait,) {}
+  #[inline(always)]
+  pub fn woken() {}
+}
+
+#[cfg(feature = "debug-locks",)]
+mod imp {
+  use super::*;
+  use std::{
+    fmt::Write,
+    string::String,
+    sync::{Mutex, MutexGuard,},
+    thread::{self, ThreadId,},
+    vec::Vec,
+  };
+
+  /// The resources held and waited on by every thread.
+  struct State {
+    /// The resource pool, resource `Id` and thread holding each held resource.
+    held: Vec<(usize, usize, ThreadId,)>,
+    /// The resource pool and resources each waiting thread is waiting for.
+    waiting: Vec<(ThreadId, PoolId, Wait,)>,
+  }
+
+  static STATE: Mutex<State> = Mutex::new(State { held: Vec::new(), waiting: Vec::new(), },);
+
+  /// Locks the state.
+  /// 
+  /// A panic while the state is locked does not leave it inconsistent.
+  fn state() -> MutexGuard<'static, State> {
+    STATE.lock().unwrap_or_else(|e,| e.into_inner(),)
+  }
+
+  /// Records that the current thread aquired a resource.
+  /// 
+  /// # Params
+  /// 
+  /// pool --- The resource pool the resource belongs to.  
+  /// id --- The `Id` of the resource.  
+  pub fn aquired(pool: PoolId, id: usize,) {
+    state().held.push((pool.address, id, thread::current().id(),),);
+  }
+  /// Records that the current thread released a resource.
+  /// 
+  /// # Params
+  /// 
+  /// pool --- The resource pool the resource belongs to.  
+  /// id --- The `Id` of the resource.  
+  pub fn released(pool: PoolId, id: usize,) {
+    let thread = thread::current().id();
+    let mut state = state();
+
+    if let Some(index) = state.held.iter().position(|&(address, held, holder,),| {
+      address == pool.address && held == id && holder == thread
+    },) {
+      state.held.swap_remove(index,);
+    }
+  }
+  /// Records that the current thread is about to wait on a resource pool.
+  /// 
+  /// # Params
+  /// 
+  /// pool --- The resource pool being waited on.  
+  /// wait --- The resources being waited for.  
+  /// 
+  /// # Panics
+  /// 
+  /// If the current thread would never be woken.
+  pub fn waiting(pool: PoolId, wait: Wait,) {
+    let thread = thread::current().id();
+    let report = {
+      let mut state = state();
+      let mut involved = Vec::new();
+
+      if blocked(&state, thread, pool, wait, &mut Vec::new(), &mut involved,) {
+        Some(report(&state, thread, pool, &involved,),)
+      } else {
+        state.waiting.push((thread, pool, wait,),);
+
+        None
+      }
+    };
+
+    //Panic after the state is unlocked.
+    if let Some(report) = report { panic!("{}", report,) }
+  }
+  /// Records that the current thread stopped waiting.
+  pub fn woken() {
+    let thread = thread::current().id();
+    let mut state = state();
+
+    if let Some(index) = state.waiting.iter()
+      .position(|&(waiting, _, _,),| waiting == thread,) {
+      state.waiting.swap_remove(index,);
+    }
+  }
+
+  /// Returns `true` if `thread` waiting for `wait` from `pool` would never be woken.
+  /// 
+  /// A thread is blocked if it could only be woken by itself or by threads which are
+  /// themselves blocked.
+  /// 
+  /// # Params
+  /// 
+  /// state --- The current state.  
+  /// thread --- The thread which is about to wait.  
+  /// pool --- The resource pool being waited on.  
+  /// wait --- The resources being waited for.  
+  /// visited --- The threads already known to be waiting.  
+  /// involved --- Collects the resource pools involved in the deadlock.  
+  fn blocked(
+    state: &State,
+    thread: ThreadId,
+    pool: PoolId,
+    wait: Wait,
+    visited: &mut Vec<ThreadId>,
+    involved: &mut Vec<PoolId>,
+  ) -> bool {
+    let holders = state.held.iter()
+      .filter(|&&(address, id, _,),| address == pool.address && match wait {
+        Wait::Any(_) => true,
+        Wait::Id(wait) => id == wait,
+      },)
+      .map(|&(_, _, holder,),| holder,)
+      .collect::<Vec<_>>();
+    let available = match wait {
+      Wait::Any(count) => pool.capacity.saturating_sub(holders.len(),) >= count,
+      Wait::Id(_) => holders.is_empty(),
+    };
+
+    if available { return false }
+
+    let blocked = holders.into_iter().all(|holder,| {
+      if holder == thread || visited.contains(&holder,) { return true }
+
+      visited.push(holder,);
+      match state.waiting.iter().find(|&&(waiting, _, _,),| waiting == holder,) {
+        Some(&(_, pool, wait,),) => blocked(state, thread, pool, wait, visited, involved,),
+        None => false,
+      }
+    },);
+
+    if blocked { involved.push(pool,); }
+
+    blocked
+  }
+  /// Describes a deadlock.
+  /// 
+  /// # Params
+  /// 
+  /// state --- The current state.  
+  /// thread --- The thread which is about to wait.  
+  /// pool --- The resource pool being waited on.  
+  /// involved --- The resource pools involved in the deadlock.  
+  fn report(state: &State, thread: ThreadId, pool: PoolId, involved: &[PoolId],) -> String {
+    let reentrant = involved.len() == 1 && state.held.iter()
+      .filter(|&&(address, _, _,),| address == pool.address,)
+      .all(|&(_, _, holder,),| holder == thread,);
+    let mut report = String::new();
+
+    if reentrant {
+      let _ = write!(report, "re-entrant aquisition of `{}` at {:#x} by {:?}",
+        pool.name, pool.address, thread,
+      );
+    } else {
+      let _ = write!(report, "deadlock detected while {:?} waits on `{}` at {:#x}",
+        thread, pool.name, pool.address,
+      );
+
+      for pool in involved.iter().rev() {
+        let holders = state.held.iter()
+          .filter(|&&(address, _, _,),| address == pool.address,)
+          .map(|&(_, id, holder,),| (id, holder,),)
+          .collect::<Vec<_>>();
+
+        let _ = write!(report, "\n  `{}` at {:#x} held by {:?}",
+          pool.name, pool.address, holders,
+        );
+      }
+    }
+
+    report
+  }
+}
+
+pub(crate) use self::imp::*;
+
+#[cfg(all(test, feature = "debug-locks",),)]
+mod tests {
+  use crate::*;
+  use std::{thread::{self, Thread,}, sync::{Arc, Barrier,}, vec::Vec,};
+
+  #[test]
+  #[should_panic(expected = "re-entrant")]
+  fn test_debug_locks_reentrant() {
+    let resource = SingleResource::<i32,>::new();
+
+    resource.get_resource::<Thread, _,>(|_, _,| {
+      resource.get_resource::<Thread, _,>(|_, _,| {},);
+    },);
+  }
+  #[test]
+  fn test_debug_locks_cycle() {
+    let first = Arc::new(SingleResource::<i32,>::new(),);
+    let second = Arc::new(MultiResource::<i32,>::new_resources(1,),);
+    let barrier = Arc::new(Barrier::new(2,),);
+
+    let handles = (0..2).map(|thread,| {
+      let (first, second, barrier,) = (first.clone(), second.clone(), barrier.clone(),);
+
+      thread::spawn(move || if thread == 0 {
+        first.get_resource::<Thread, _,>(|_, _,| {
+          barrier.wait();
+          second.get_resource::<Thread, _,>(|_, _,| {},);
+        },);
+      } else {
+        second.get_resource::<Thread, _,>(|_, _,| {
+          barrier.wait();
+          first.get_resource::<Thread, _,>(|_, _,| {},);
+        },);
+      },)
+    },).collect::<Vec<_>>();
+    let panicked = handles.into_iter()
+      .map(|handle,| handle.join().is_err(),)
+      .filter(|&panicked,| panicked,)
+      .count();
+
+    assert_eq!(panicked, 1,);
+  }
+}
@@ -0,0 +1,35 @@
+//! Defines the errors returned when a resource pool cannot provide a resource.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-18
+
+use core::fmt;
+
+/// The reasons a resource pool can fail to provide a resource.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub enum PoolError {
+  /// The maximum number of threads were already waiting on the resource pool.
+  QueueFull,
+  /// The `CancelToken` the thread was waiting with was cancelled.
+  Cancelled,
+  /// The resource pool was closed.
+  Closed,
+}
+
+impl PoolError {
+  /// Every `PoolError`.
+  pub const ALL: [PoolError; 3] = [PoolError::QueueFull, PoolError::Cancelled, PoolError::Closed,];
+}
+
+impl fmt::Display for PoolError {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    match self {
+      PoolError::QueueFull => write!(fmt, "too many threads are waiting on the resource pool",),
+      PoolError::Cancelled => write!(fmt, "waiting on the resource pool was cancelled",),
+      PoolError::Closed => write!(fmt, "the resource pool is closed",),
+    }
+  }
+}
+
+#[cfg(feature = "std",)]
+impl std::error::Error for PoolError {}
@@ -0,0 +1,137 @@
+//! Defines callbacks which instrument the use of a resource pool's resources.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-18
+
+use crate::Resource;
+use core::time::Duration;
+
+/// Callbacks run as the resources of a resource pool are used.
+/// 
+/// Every callback does nothing by default. Install hooks on a resource pool using
+/// `SingleResource::with_hooks` or `MultiResource::with_hooks`, which take a `'static`
+/// reference so hooks can be installed on a `static` resource pool.
+/// 
+/// ```rust
+/// use bottleneck::*;
+/// use std::{thread::Thread, time::Duration};
+/// 
+/// /// Logs resources which were held for too long.
+/// struct SlowHolds;
+/// 
+/// impl PoolHooks<Vec<u8>> for SlowHolds {
+///   fn on_release(&self, id: usize, _: &mut Vec<u8>, held_for: Duration,) {
+///     if held_for > Duration::from_secs(1) { eprintln!("resource {} held for {:?}", id, held_for) }
+///   }
+/// }
+/// 
+/// static HOOKS: SlowHolds = SlowHolds;
+/// static BUFFER: SingleResource<Vec<u8>> = SingleResource::INIT.with_hooks(&HOOKS);
+/// 
+/// let pool = MultiResource::<Vec<u8>>::new_resources(4).with_hooks(&HOOKS);
+/// 
+/// BUFFER.get_resource::<Thread, _>(|_, buffer,| buffer.push(1));
+/// pool.get_resource::<Thread, _>(|_, buffer,| buffer.push(1));
+/// ```
+pub trait PoolHooks<R,> {
+  /// Called after the resource `id` is aquired and before it is used.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The Id of the resource.  
+  /// resource --- The resource.  
+  #[inline]
+  fn on_acquire(&self, id: usize, resource: &mut R,) { let _ = (id, resource,); }
+  /// Called after the resource `id` is used and before the reuse pollicy is checked.
+  /// 
+  /// Without the `std` feature `held_for` is always zero.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The Id of the resource.  
+  /// resource --- The resource.  
+  /// held_for --- How long the resource was held.  
+  #[inline]
+  fn on_release(&self, id: usize, resource: &mut R, held_for: Duration,) {
+    let _ = (id, resource, held_for,);
+  }
+  /// Called after a new resource is created to replace the discarded resource `id`.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The Id of the resource.  
+  /// resource --- The new resource.  
+  #[inline]
+  fn on_create(&self, id: usize, resource: &mut R,) { let _ = (id, resource,); }
+  /// Called before the resource `id` is discarded by the reuse pollicy.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The Id of the resource.  
+  /// resource --- The discarded resource.  
+  #[inline]
+  fn on_discard(&self, id: usize, resource: &mut R,) { let _ = (id, resource,); }
+}
+
+/// The hooks installed on a resource pool.
+/// 
+/// The hooks are only ever borrowed from a `'static` reference; a pointer is stored so
+/// the resource pool does not require `R: 'static`.
+pub(crate) struct Hooks<R,>(Option<*const (dyn PoolHooks<R,> + Sync)>,);
+
+unsafe impl<R,> Send for Hooks<R,> {}
+unsafe impl<R,> Sync for Hooks<R,> {}
+
+impl<R,> Hooks<R,> {
+  /// No hooks.
+  pub const NONE: Self = Hooks(None,);
+
+  /// Installs `hooks`.
+  /// 
+  /// # Params
+  /// 
+  /// hooks --- The hooks to install.  
+  #[inline]
+  pub const fn new(hooks: &'static (dyn PoolHooks<R,> + Sync),) -> Self
+    where R: 'static, { Hooks(Some(hooks,),) }
+  /// Returns the installed hooks.
+  #[inline]
+  fn get(&self,) -> Option<&(dyn PoolHooks<R,> + Sync)> {
+    self.0.map(|hooks,| unsafe { &*hooks },)
+  }
+  /// Returns `true` if hooks are installed.
+  #[inline]
+  pub fn is_installed(&self,) -> bool { self.0.is_some() }
+  /// Runs `PoolHooks::on_acquire`.
+  #[inline]
+  pub fn on_acquire(&self, id: usize, resource: &mut R,) {
+    if let Some(hooks) = self.get() { hooks.on_acquire(id, resource,) }
+  }
+  /// Runs `PoolHooks::on_release`.
+  #[inline]
+  pub fn on_release(&self, id: usize, resource: &mut R, held_for: Duration,) {
+    if let Some(hooks) = self.get() { hooks.on_release(id, resource, held_for,) }
+  }
+  /// Runs `PoolHooks::on_create`.
+  #[inline]
+  pub fn on_create(&self, id: usize, resource: &mut R,) {
+    if let Some(hooks) = self.get() { hooks.on_create(id, resource,) }
+  }
+  /// Runs `PoolHooks::on_discard`.
+  #[inline]
+  pub fn on_discard(&self, id: usize, resource: &mut R,) {
+    if let Some(hooks) = self.get() { hooks.on_discard(id, resource,) }
+  }
+  /// Discards a resource and replaces it with a new resource.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The Id of the resource.  
+  /// resource --- The resource to replace.  
+  pub fn replace(&self, id: usize, resource: &mut R,)
+    where R: Resource, {
+    self.on_discard(id, resource,);
+    *resource = R::new();
+    self.on_create(id, resource,);
+  }
+}
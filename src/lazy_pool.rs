@@ -0,0 +1,205 @@
+//! Defines resource pools which are created the first time they are used.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-18
+
+use super::*;
+use crate::pollicy::*;
+use core::{
+  hint,
+  ops::Deref,
+  cell::UnsafeCell,
+  mem::MaybeUninit,
+  sync::atomic::{AtomicUsize, Ordering,},
+};
+use alloc::vec::Vec;
+
+/// The state of a `LazyPool` which has not been created.
+const UNINIT: usize = 0;
+/// The state of a `LazyPool` which is being created.
+const BUSY: usize = 1;
+/// The state of a `LazyPool` which has been created.
+const READY: usize = 2;
+/// The state of a `LazyPool` which panicked while being created.
+const POISONED: usize = 3;
+
+/// Creates a resource pool the first time a `LazyPool` is used.
+pub trait PoolInit<Pool,> {
+  /// Creates the resource pool.
+  fn init(self,) -> Pool;
+}
+
+impl<Pool,> PoolInit<Pool,> for fn() -> Pool {
+  #[inline]
+  fn init(self,) -> Pool { self() }
+}
+
+/// Creates a `MultiResource` of `self.0` resources created by `self.1`.
+impl<R, P,> PoolInit<MultiResource<R, P,>,> for (usize, fn() -> R,)
+  where P: ConstPollicy, {
+  fn init(self,) -> MultiResource<R, P,> {
+    let (count, init,) = self;
+
+    MultiResource::with_resources((0..count).map(|_,| init(),).collect::<Vec<_>>(),)
+  }
+}
+
+/// A `MultiResource` which creates its resources the first time it is used.
+pub type LazyMultiResource<R, Pollicy = Reuse,> = LazyPool<
+  MultiResource<R, Pollicy,>,
+  (usize, fn() -> R,),
+>;
+
+/// A resource pool which is created the first time it is used.
+/// 
+/// Unlike the `INIT` constants a `LazyPool` can hold any resource pool in a `static`.
+/// 
+/// ```rust
+/// use bottleneck::*;
+/// use std::thread::Thread;
+/// 
+/// static BUFFERS: LazyMultiResource<Vec<u8>> = MultiResource::lazy(4, || vec![0; 64],);
+/// 
+/// BUFFERS.get_resource::<Thread, _,>(|_, buffer,| assert_eq!(buffer.len(), 64,),);
+/// ```
+pub struct LazyPool<Pool, Init = fn() -> Pool,> {
+  /// The state of the resource pool.
+  state: AtomicUsize,
+  /// Creates the resource pool.
+  init: UnsafeCell<Option<Init>>,
+  /// The resource pool once it is created.
+  pool: UnsafeCell<MaybeUninit<Pool>>,
+}
+
+unsafe impl<Pool, Init,> Send for LazyPool<Pool, Init,>
+  where Pool: Send,
+    Init: Send, {}
+unsafe impl<Pool, Init,> Sync for LazyPool<Pool, Init,>
+  where Pool: Send + Sync,
+    Init: Send, {}
+
+impl<Pool, Init,> LazyPool<Pool, Init,> {
+  /// Creates a resource pool which is created by `init` when it is first used.
+  /// 
+  /// # Params
+  /// 
+  /// init --- Creates the resource pool.  
+  pub const fn new(init: Init,) -> Self {
+    Self {
+      state: AtomicUsize::new(UNINIT,),
+      init: UnsafeCell::new(Some(init,),),
+      pool: UnsafeCell::new(MaybeUninit::uninit(),),
+    }
+  }
+}
+
+impl<Pool, Init,> LazyPool<Pool, Init,>
+  where Init: PoolInit<Pool,>, {
+  /// Gets the resource pool, creating it if this is the first use.
+  /// 
+  /// Other threads spin while the resource pool is being created.
+  /// 
+  /// # Panics
+  /// 
+  /// If creating the resource pool panicked.
+  pub fn force(&self,) -> &Pool {
+    /// Poisons the resource pool if creating it panics.
+    struct Poison<'a,>(&'a AtomicUsize,);
+
+    impl Drop for Poison<'_,> {
+      fn drop(&mut self,) { self.0.store(POISONED, Ordering::Release,) }
+    }
+
+    loop {
+      match self.state.compare_exchange(UNINIT, BUSY, Ordering::Acquire, Ordering::Acquire,) {
+        Ok(_) => {
+          let poison = Poison(&self.state,);
+          //Only the thread which set `BUSY` accesses `init` and `pool`.
+          let init = unsafe { (*self.init.get()).take() }
+            .expect("`LazyPool` initialiser missing",);
+          let pool = init.init();
+
+          unsafe { (*self.pool.get()).as_mut_ptr().write(pool,); }
+          core::mem::forget(poison,);
+          self.state.store(READY, Ordering::Release,);
+        },
+        Err(READY) => return unsafe { &*(*self.pool.get()).as_ptr() },
+        Err(POISONED) => panic!("`LazyPool` panicked while being created"),
+        Err(_) => hint::spin_loop(),
+      }
+    }
+  }
+}
+
+impl<Pool, Init,> Deref for LazyPool<Pool, Init,>
+  where Init: PoolInit<Pool,>, {
+  type Target = Pool;
+
+  #[inline]
+  fn deref(&self,) -> &Self::Target { self.force() }
+}
+
+impl<Pool, Init,> Drop for LazyPool<Pool, Init,> {
+  fn drop(&mut self,) {
+    if *self.state.get_mut() == READY {
+      unsafe { core::ptr::drop_in_place((*self.pool.get()).as_mut_ptr(),); }
+    }
+  }
+}
+
+unsafe impl<Pool, Init,> ResourcePool for LazyPool<Pool, Init,>
+  where Pool: ResourcePool,
+    Init: PoolInit<Pool,>, {
+  type Resource = Pool::Resource;
+
+  #[inline]
+  fn get_resource<P, F,>(&self, f: F,)
+    where P: Park,
+      F: FnOnce(usize, &mut Self::Resource,), { self.force().get_resource::<P, F,>(f,) }
+  #[inline]
+  fn try_get_resource<F,>(&self, f: F,) -> bool
+    where F: FnOnce(usize, &mut Self::Resource,), { self.force().try_get_resource(f,) }
+}
+
+unsafe impl<Pool, Init,> RwResourcePool for LazyPool<Pool, Init,>
+  where Pool: RwResourcePool,
+    Init: PoolInit<Pool,>, {
+  #[inline]
+  fn read_resource<P, F,>(&self, f: F,)
+    where P: Park,
+      F: FnOnce(usize, &Self::Resource,), { self.force().read_resource::<P, F,>(f,) }
+  #[inline]
+  fn try_read_resource<F,>(&self, f: F,) -> bool
+    where F: FnOnce(usize, &Self::Resource,), { self.force().try_read_resource(f,) }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use std::{vec, thread::{self, Thread,}, sync::atomic::AtomicUsize, vec::Vec,};
+
+  #[test]
+  fn test_lazy_pool() {
+    static CREATED: AtomicUsize = AtomicUsize::new(0,);
+    static RESOURCE: LazyPool<RwResource<Vec<u8>,>,> = LazyPool::new(|| {
+      CREATED.fetch_add(1, Ordering::Relaxed,);
+
+      RwResource::with_resource(vec![1, 2, 3,],)
+    },);
+
+    let handles = (0..8).map(|_,| thread::spawn(|| {
+      RESOURCE.read_resource::<Thread, _,>(|_, r,| assert_eq!(*r, [1, 2, 3,],),);
+    },),).collect::<Vec<_>>();
+
+    for handle in handles { handle.join().unwrap(); }
+
+    assert_eq!(CREATED.load(Ordering::Relaxed,), 1,);
+  }
+  #[test]
+  fn test_lazy_pool_poisoned() {
+    static RESOURCE: LazyPool<SingleResource<i32,>,> = LazyPool::new(|| panic!(),);
+
+    assert!(thread::spawn(|| RESOURCE.force().try_get_resource(|_, _,| {},),).join().is_err(),);
+    assert!(thread::spawn(|| RESOURCE.force().try_get_resource(|_, _,| {},),).join().is_err(),);
+  }
+}
@@ -24,29 +24,64 @@
 //! ```
 //! 
 //! Author --- daniel.bechaz@gmail.com  
-//! Last Moddified --- 2019-06-15
+//! Last Moddified --- 2026-10-18
 
 #![deny(missing_docs,)]
 #![no_std]
-#![feature(const_fn, const_raw_ptr_deref, const_vec_new, vec_remove_item,)]
 
 extern crate alloc;
-#[cfg(test,)]
+#[cfg(any(test, feature = "std",),)]
 extern crate std;
+//Lets derived impls name `::bottleneck` in this crate's tests.
+#[cfg(all(test, feature = "derive",),)]
+extern crate self as bottleneck;
 
 use sync_stack::Park;
 
 mod resource;
+mod error;
+mod hooks;
+mod debug_locks;
+mod trace;
+mod wait_queue;
+mod spin_lock;
 pub mod pollicy;
 mod single_resource;
 mod multi_resource;
+mod rw_resource;
+mod lazy_pool;
+#[cfg(feature = "std",)]
+mod thread_cache;
+#[cfg(feature = "std",)]
+mod sharded_resource;
+#[cfg(feature = "std",)]
+pub mod openmetrics;
+mod resource_pools;
+pub mod stats;
+pub mod registry;
 
 pub use self::{
+  error::*,
+  hooks::PoolHooks,
+  resource::*,
   single_resource::*,
   multi_resource::*,
+  rw_resource::*,
+  lazy_pool::*,
+  resource_pools::*,
 };
+pub use self::wait_queue::CancelToken;
+#[cfg(feature = "std",)]
+pub use self::{thread_cache::*, sharded_resource::*,};
+#[cfg(feature = "derive",)]
+pub use bottleneck_derive::{Resource, ConstResource,};
 
 /// Defines the behaviour of a resource pool.
+/// 
+/// # Safety
+/// 
+/// A resource passed to a `get_resource` or `try_get_resource` closure must not be read or
+/// written by any other closure until it returns.
 pub unsafe trait ResourcePool {
   /// Defines the type of resource in this resource pool.
   type Resource;
@@ -60,6 +95,11 @@ pub unsafe trait ResourcePool {
   /// # Params
   /// 
   /// f --- The closure to run once a resource is aquired.  
+  /// 
+  /// # Panics
+  /// 
+  /// Resource pools which can be closed panic if they are closed before a resource is
+  /// aquired; they provide a `checked_get_resource` which returns `Err(Closed)` instead.
   fn get_resource<P, F,>(&self, f: F,)
     where P: Park,
       F: FnOnce(usize, &mut Self::Resource,),;
@@ -74,6 +114,61 @@ pub unsafe trait ResourcePool {
     where F: FnOnce(usize, &mut Self::Resource,),;
 }
 
+/// Defines the behaviour of a resource pool which many threads can read at once.
+/// 
+/// `get_resource` gives a single thread exclusive access to write a resource.
+/// 
+/// # Safety
+/// 
+/// A resource passed to a `read_resource` closure must not be written by any thread
+/// until the closure returns, and a resource passed to a `get_resource` closure must not
+/// be read or written by any other closure until it returns.
+pub unsafe trait RwResourcePool: ResourcePool {
+  /// Reads a resource from the resource pool.
+  /// 
+  /// The `RwResourcePool` will allow any number of threads to read a resource at once
+  /// while no thread is writing it.
+  /// 
+  /// The `usize` returned with the resource indicates its `Id` in the resource pool.
+  /// 
+  /// # Params
+  /// 
+  /// f --- The closure to run once a resource is aquired.  
+  fn read_resource<P, F,>(&self, f: F,)
+    where P: Park,
+      F: FnOnce(usize, &Self::Resource,),;
+  /// A non blocking equivelant of `read_resource`.
+  /// 
+  /// Returns `true` if the closure was executed.
+  /// 
+  /// # Params
+  /// 
+  /// f --- The closure to run once a resource is aquired.  
+  fn try_read_resource<F,>(&self, f: F,) -> bool
+    where F: FnOnce(usize, &Self::Resource,),;
+  /// Writes a resource from the resource pool.
+  /// 
+  /// This is equivelant to `get_resource`.
+  /// 
+  /// # Params
+  /// 
+  /// f --- The closure to run once a resource is aquired.  
+  #[inline]
+  fn write_resource<P, F,>(&self, f: F,)
+    where P: Park,
+      F: FnOnce(usize, &mut Self::Resource,), { self.get_resource::<P, F,>(f,) }
+  /// A non blocking equivelant of `write_resource`.
+  /// 
+  /// Returns `true` if the closure was executed.
+  /// 
+  /// # Params
+  /// 
+  /// f --- The closure to run once a resource is aquired.  
+  #[inline]
+  fn try_write_resource<F,>(&self, f: F,) -> bool
+    where F: FnOnce(usize, &mut Self::Resource,), { self.try_get_resource(f,) }
+}
+
 /// Tags a type as a valid resource.
 pub trait Resource: Sized {
   /// Creates a new instance of the resource.
@@ -85,3 +180,11 @@ pub trait ConstResource: Resource {
   /// The inital value.
   const INIT: Self;
 }
+
+/// A resource which can be returned to its inital value in place.
+pub trait Reset {
+  /// Resets this resource instance to its inital value.
+  /// 
+  /// Any memory allocated by the resource instance should be kept for reuse.
+  fn reset(&mut self,);
+}
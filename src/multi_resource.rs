@@ -1,31 +1,61 @@
 //! Author --- daniel.bechaz@gmail.com  
-//! Last Moddified --- 2019-06-15
+//! Last Moddified --- 2026-10-18
 
 use super::*;
-use crate::pollicy::*;
-use sync_stack::*;
-use core::marker::PhantomData;
+use crate::{pollicy::*, trace, hooks::Hooks, registry::{Introspect, PoolInfo,}, stats::PoolStats, debug_locks::{self, PoolId, Wait,}, wait_queue::{WaitQueue, Waiting,}, spin_lock::SpinLock,};
+use core::{mem, cell::UnsafeCell, sync::atomic::{AtomicBool, AtomicUsize, Ordering,},};
 use alloc::vec::Vec;
 
 /// Stores a multiple resource instances and provides mutual exclusion to all of them.
 pub struct MultiResource<R, Pollicy = Reuse,> {
   /// The collection of resources to use.
-  resources: Vec<R>,
+  resources: Vec<UnsafeCell<R>>,
   /// The indexes of the resources not currently in use.
-  available_resources: SingleResource<Vec<usize>, Reuse,>,
-  /// A stack of threads waiting to access a resource.
-  sync_stack: SyncStack,
-  _data: PhantomData<Pollicy>,
+  available_resources: SpinLock<Vec<usize>>,
+  /// A queue of threads waiting to access a resource.
+  pub(crate) queue: WaitQueue,
+  /// A queue of threads waiting to access specific resources or several resources at once.
+  id_queue: WaitQueue,
+  /// The number of times each resource instance has been used.
+  uses: Vec<AtomicUsize>,
+  /// When each resource instance was created.
+  created: Vec<Created>,
+  /// The maximum number of threads which can wait in `queue` when aquiring fallibly.
+  max_waiters: usize,
+  /// A flag indicating if the resource pool has been closed.
+  closed: AtomicBool,
+  /// The reuse pollicy of this resource pool.
+  pollicy: Pollicy,
+  /// The callbacks run as the resources are used.
+  pub(crate) hooks: Hooks<R,>,
+  /// The name of the resource pool.
+  name: Option<&'static str>,
+  /// Statistics about how the resources have been used.
+  pub(crate) stats: PoolStats,
 }
 
+unsafe impl<R, Pol,> Sync for MultiResource<R, Pol,>
+  where R: Send,
+    Pol: Sync, {}
+
 impl<R, P,> MultiResource<R, P,>
-  where R: Resource, {
+  where R: Resource,
+    P: ConstPollicy, {
   /// An empty resource pool.
+  #[allow(clippy::declare_interior_mutable_const,)]
   pub const INIT: Self = Self {
     resources: Vec::new(),
-    available_resources: SingleResource::INIT,
-    sync_stack: SyncStack::new(),
-    _data: PhantomData,
+    available_resources: SpinLock::new(Vec::new(),),
+    queue: WaitQueue::new(),
+    id_queue: WaitQueue::new(),
+    uses: Vec::new(),
+    created: Vec::new(),
+    max_waiters: usize::MAX,
+    closed: AtomicBool::new(false,),
+    pollicy: P::INIT,
+    hooks: Hooks::NONE,
+    name: None,
+    stats: PoolStats::new(),
   };
 
   /// Creates a new resource pool of `count` new elements.
@@ -34,281 +64,694 @@ impl<R, P,> MultiResource<R, P,>
       (0..count).map(|_,| R::new(),).collect(),
     )
   }
+  /// Creates a resource pool of `count` elements created by `init` when it is first used.
+  /// 
+  /// Unlike `new_resources` this can be used to initialise a `static`.
+  /// 
+  /// # Params
+  /// 
+  /// count --- The number of resources to create.  
+  /// init --- Creates each resource.  
+  pub const fn lazy(count: usize, init: fn() -> R,) -> LazyMultiResource<R, P,> {
+    LazyPool::new((count, init,),)
+  }
 }
 
-impl<R,> MultiResource<R, Reuse,>
-  where R: Resource, {
-  /// Attempts to aquire the resource and run the closure.
-  /// 
-  /// If the resource could not be locked the closure is returned.
-  fn attempt_aquire<F,>(&self, f: F,) -> Option<F>
-    where F: FnOnce(usize, &mut R,), {
-    struct Finish<'pool, R,>
-      where R: Resource, {
-      resource: usize,
-      pool: &'pool MultiResource<R, Reuse,>,
-    }
+/// Returns a selector which removes the resource `id` from the available resources.
+/// 
+/// The order of the remaining available resources is preserved.
+/// 
+/// # Params
+/// 
+/// id --- The Id of the resource to select.  
+fn select_id(id: usize,) -> impl FnOnce(&mut Vec<usize>,) -> Option<usize> {
+  move |resources,| resources.iter()
+    .position(|&resource,| resource == id,)
+    .map(|index,| resources.remove(index,),)
+}
 
-    impl<R,> Drop for Finish<'_, R,>
-      where R: Resource, {
-      fn drop(&mut self,) {
-        let release = |_, resources: &mut Vec<usize>,| resources.push(self.resource,);
+/// Checks the reuse pollicy and releases a claimed resource when dropped.
+struct Finish<'pool, R, Pol,>
+  where R: Resource,
+    Pol: PolicyKind<R,>, {
+  /// The Id of the resource.
+  resource: usize,
+  /// Times how long the resource is held.
+  timer: Timer,
+  /// The resource pool the resource belongs to.
+  pool: &'pool MultiResource<R, Pol,>,
+}
 
-        //Release the resource.
-        while self.pool.available_resources.try_get_resource::<_,>(release,) {
-          core::sync::atomic::spin_loop_hint();
-        };
-      }
-    }
+impl<R, Pol,> Drop for Finish<'_, R, Pol,>
+  where R: Resource,
+    Pol: PolicyKind<R,>, {
+  fn drop(&mut self,) { self.pool.release(self.resource, self.timer,) }
+}
 
-    let mut resource = None;
-    //Aquire a resource.
-    self.available_resources.try_get_resource(
-      |_, resources,| resource = resources.pop(),
-    );
+/// Checks the reuse pollicy and releases several claimed resources when dropped.
+struct FinishMany<'pool, R, Pol,>
+  where R: Resource,
+    Pol: PolicyKind<R,>, {
+  /// The Ids of the resources.
+  resources: Vec<usize>,
+  /// Times how long the resources are held.
+  timer: Timer,
+  /// The resource pool the resources belong to.
+  pool: &'pool MultiResource<R, Pol,>,
+}
 
-    match resource {
-      None => Some(f),
-      Some(resource) => {
-        let _ = Finish::<R,> { resource, pool: self, };
+impl<R, Pol,> Drop for FinishMany<'_, R, Pol,>
+  where R: Resource,
+    Pol: PolicyKind<R,>, {
+  fn drop(&mut self,) {
+    for &resource in self.resources.iter() { self.pool.release(resource, self.timer,) }
+  }
+}
 
-        f(resource, unsafe { &mut self.resources_mut()[resource] },);
+impl<R, Pol,> MultiResource<R, Pol,>
+  where R: Resource,
+    Pol: PolicyKind<R,>, {
+  /// Returns `true` if the pollicy, hooks or statistics use how long resources are held.
+  #[inline]
+  pub(crate) fn timed(&self,) -> bool {
+    self.pollicy.timed() || self.hooks.is_installed() || self.stats.has_histograms()
+  }
+  /// Checks the reuse pollicy and releases a resource after it has been used.
+  /// 
+  /// # Params
+  /// 
+  /// resource --- The Id of the resource to release.  
+  /// timer --- Timed how long the resource was held.  
+  fn release(&self, resource: usize, timer: Timer,) {
+    self.recycle(resource, resource, timer,);
+    //Release the resource.
+    self.release_resource(resource,);
+  }
+  /// Checks the reuse pollicy after a resource has been used without releasing it.
+  /// 
+  /// # Params
+  /// 
+  /// resource --- The Id of the resource which was used.  
+  /// id --- The Id of the resource given to the hooks and the reuse pollicy.  
+  /// timer --- Timed how long the resource was held.  
+  pub(crate) fn recycle(&self, resource: usize, id: usize, timer: Timer,) {
+    let instance = unsafe { &mut *self.resource(resource,) };
+    let uses = &self.uses[resource];
+    let created = &self.created[resource];
+    let usage = Usage {
+      id,
+      uses: uses.fetch_add(1, Ordering::Relaxed,) + 1,
+      held: timer.elapsed(),
+      age: unsafe { created.age(timer,) },
+    };
 
-        None
-      },
+    self.stats.held(usage.held,);
+    self.hooks.on_release(id, instance, usage.held,);
+    //Check if we reuse the resource.
+    if !self.pollicy.reuse(instance, &usage,) {
+      self.hooks.replace(id, instance,);
+      trace::recreated(self.pool_id(), id,);
+      self.stats.recreated();
+      uses.store(0, Ordering::Relaxed,);
+      unsafe { created.reset(self.timed(),); }
     }
   }
-}
+  /// Attempts to claim a resource.
+  /// 
+  /// Returns `None` if no resource could be taken.
+  /// 
+  /// # Params
+  /// 
+  /// select --- Selects the resource to aquire from the available resources.  
+  fn attempt_aquire<S,>(&self, select: S,) -> Option<Finish<'_, R, Pol,>>
+    where S: FnOnce(&mut Vec<usize>,) -> Option<usize>, {
+    //Aquire a resource.
+    self.take_resource(select,).map(|resource,| self.claim(resource,),)
+  }
+  /// Claims a resource which has been taken from the available resources.
+  /// 
+  /// # Params
+  /// 
+  /// resource --- The Id of the taken resource.  
+  fn claim(&self, resource: usize,) -> Finish<'_, R, Pol,> {
+    self.stats.aquired();
 
-impl<R,> MultiResource<R, NoReuse,>
-  where R: Resource, {
-  /// Attempts to aquire the resource and run the closure.
+    Finish { resource, timer: Timer::start_if(self.timed(),), pool: self, }
+  }
+  /// Runs the closure with a claimed resource.
+  /// 
+  /// # Params
   /// 
-  /// If the resource could not be locked the closure is returned.
-  fn attempt_aquire<F,>(&self, f: F,) -> Option<F>
+  /// finish --- Releases the claimed resource.  
+  /// f --- The closure to run.  
+  fn run<F,>(&self, finish: Finish<'_, R, Pol,>, f: F,)
     where F: FnOnce(usize, &mut R,), {
-    struct Finish<'pool, R,>
-      where R: Resource, {
-      resource: usize,
-      pool: &'pool MultiResource<R, NoReuse,>,
-    }
-
-    impl<R,> Drop for Finish<'_, R,>
-      where R: Resource, {
-      fn drop(&mut self,) {
-        //Replace the resource.
-        unsafe { self.pool.resources_mut()[self.resource] = R::new(); }
+    let resource = finish.resource;
 
-        let release = |_, resources: &mut Vec<usize>,| resources.push(self.resource,);
+    debug_locks::aquired(self.pool_id(), resource,);
 
-        //Release the resource.
-        while self.pool.available_resources.try_get_resource(release,) {
-          core::sync::atomic::spin_loop_hint();
-        };
-      }
-    }
-
-    let mut resource = None;
-    //Aquire a resource.
-    self.available_resources.try_get_resource(
-      |_, resources,| resource = resources.pop(),
-    );
+    let instance = unsafe { &mut *self.resource(resource,) };
+    let _holding = trace::holding(self.pool_id(), resource,);
 
-    match resource {
-      None => Some(f),
-      Some(resource) => {
-        let _ = Finish::<R,> { resource, pool: self, };
+    self.hooks.on_acquire(resource, instance,);
+    f(resource, instance,);
+  }
+  /// Gets a resource from the resource pool unless too many threads are waiting.
+  /// 
+  /// Returns `Err(QueueFull)` without running the closure if the limit set by
+  /// `with_max_waiters` was reached when this thread needed to wait, or `Err(Closed)` if
+  /// the resource pool was closed before a resource was aquired.
+  /// 
+  /// # Params
+  /// 
+  /// f --- The closure to run once a resource is aquired.  
+  #[inline]
+  pub fn checked_get_resource<P, F,>(&self, f: F,) -> Result<(), PoolError>
+    where P: Park,
+      F: FnOnce(usize, &mut R,), { self.get_resource_with_priority::<P, F,>(0, f,) }
+  /// Gets a resource from the resource pool, waiting with `priority`.
+  /// 
+  /// Released resources are handed to the waiting thread with the highest priority
+  /// first and to waiting threads with equal priorities in the order they arrived, so
+  /// threads which have not waited cannot take them first. `get_resource` waits with a
+  /// priority of `0`.
+  /// 
+  /// Returns `Err(QueueFull)` without running the closure if the limit set by
+  /// `with_max_waiters` was reached when this thread needed to wait.
+  /// 
+  /// # Params
+  /// 
+  /// priority --- The priority to wait with, higher priorities are served first.  
+  /// f --- The closure to run once a resource is aquired.  
+  pub fn get_resource_with_priority<P, F,>(&self, priority: usize, f: F,) -> Result<(), PoolError>
+    where P: Park,
+      F: FnOnce(usize, &mut R,), {
+    //Attempt to aquire a resource, waiting for a resource to be released between attempts.
+    let waiting = Waiting { priority, ..self.waiting() };
 
-        f(resource, unsafe { &mut self.resources_mut()[resource] },);
+    self.wait_resource::<P, F,>(waiting, f,)
+  }
+  /// Gets a resource from the resource pool unless `token` is cancelled first.
+  /// 
+  /// Returns `Err(Cancelled)` without running the closure if `token` was cancelled
+  /// before a resource was aquired, or `Err(QueueFull)` if the limit set by
+  /// `with_max_waiters` was reached when this thread needed to wait.
+  /// 
+  /// # Params
+  /// 
+  /// token --- Cancels the aquisition.  
+  /// f --- The closure to run once a resource is aquired.  
+  pub fn get_resource_cancellable<P, F,>(&self, token: &CancelToken, f: F,) -> Result<(), PoolError>
+    where P: Park,
+      F: FnOnce(usize, &mut R,), {
+    let waiting = Waiting { cancel: Some(token,), ..self.waiting() };
 
-        None
-      },
-    }
+    self.wait_resource::<P, F,>(waiting, f,)
   }
-}
+  /// Gets a resource from the resource pool, waiting as described by `waiting`.
+  /// 
+  /// # Params
+  /// 
+  /// waiting --- How to wait for a resource.  
+  /// f --- The closure to run once a resource is aquired.  
+  fn wait_resource<P, F,>(&self, waiting: Waiting, f: F,) -> Result<(), PoolError>
+    where P: Park,
+      F: FnOnce(usize, &mut R,), {
+    //Attempt to aquire a resource, waiting for a resource to be released between attempts.
+    self.queue.wait_handed::<P, _, _, _, _,>(
+      waiting, || self.attempt_aquire(Vec::pop,),
+      |resource,| self.claim(resource,),
+      |handed,| self.pass_on(handed,),
+      self.pool_id(), Wait::Any(1,),
+    ).map(|finish,| self.run(finish, f,),)
+  }
+  /// Gets the resource with Id `id` from the resource pool.
+  /// 
+  /// Unlike `get_resource` this waits for the specific resource to become available
+  /// rather than any resource.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The Id of the resource to aquire.  
+  /// f --- The closure to run once the resource is aquired.  
+  /// 
+  /// # Panics
+  /// 
+  /// If `id` is not the Id of a resource in this pool or if the resource pool is closed;
+  /// see `checked_get_resource_by_id`.
+  pub fn get_resource_by_id<P, F,>(&self, id: usize, f: F,)
+    where P: Park,
+      F: FnOnce(usize, &mut R,), {
+    if let Err(error) = self.checked_get_resource_by_id::<P, F,>(id, f,) { panic!("{}", error,) }
+  }
+  /// Gets the resource with Id `id` from the resource pool unless it is closed.
+  /// 
+  /// Returns `Err(Closed)` without running the closure if the resource pool was closed
+  /// before the resource was aquired.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The Id of the resource to aquire.  
+  /// f --- The closure to run once the resource is aquired.  
+  /// 
+  /// # Panics
+  /// 
+  /// If `id` is not the Id of a resource in this pool.
+  pub fn checked_get_resource_by_id<P, F,>(&self, id: usize, f: F,) -> Result<(), PoolError>
+    where P: Park,
+      F: FnOnce(usize, &mut R,), {
+    assert!(id < self.len(), "`{}` is not a resource Id", id,);
 
-impl<R, P,> MultiResource<R, Pollicy<P,>,>
-  where R: Resource,
-    P: ReusePollicy<R,>, {
-  /// Attempts to aquire the resource and run the closure.
+    //Attempt to aquire the resource, waiting for a resource to be released between attempts.
+    self.id_queue.wait_with::<P, _, _, _,>(
+      self.id_waiting(), || self.attempt_aquire(select_id(id,),),
+      || {},
+      self.pool_id(), Wait::Id(id,),
+    ).map(|finish,| self.run(finish, f,),)
+  }
+  /// A non blocking equivelant of `get_resource_by_id`.
   /// 
-  /// If the resource could not be locked the closure is returned.
-  fn attempt_aquire<F,>(&self, f: F,) -> Option<F>
+  /// Returns `true` if the closure was executed.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The Id of the resource to aquire.  
+  /// f --- The closure to run once the resource is aquired.  
+  pub fn try_get_resource_by_id<F,>(&self, id: usize, f: F,) -> bool
     where F: FnOnce(usize, &mut R,), {
-    struct Finish<'pool, R, P,>
-      where R: Resource,
-        P: ReusePollicy<R,>, {
-      resource: usize,
-      pool: &'pool MultiResource<R, Pollicy<P,>,>,
+    //Attempt to aquire the resource.
+    match self.attempt_aquire(select_id(id,),) {
+      Some(finish) => { self.run(finish, f,); true },
+      None => false,
     }
+  }
+  /// Gets `count` distinct resources from the resource pool at once.
+  /// 
+  /// The resources are aquired all together or not at all so a thread never holds some
+  /// of the resources while it waits for the rest.
+  /// 
+  /// The Ids of the resources are passed to the closure in the same order as the
+  /// resources.
+  /// 
+  /// # Params
+  /// 
+  /// count --- The number of resources to aquire.  
+  /// f --- The closure to run once the resources are aquired.  
+  /// 
+  /// # Panics
+  /// 
+  /// If `count` is greater than the number of resources in this pool or if the resource
+  /// pool is closed; see `checked_get_resources`.
+  pub fn get_resources<P, F,>(&self, count: usize, f: F,)
+    where P: Park,
+      F: FnOnce(&[usize], &mut [&mut R],), {
+    if let Err(error) = self.checked_get_resources::<P, F,>(count, f,) { panic!("{}", error,) }
+  }
+  /// Gets `count` distinct resources from the resource pool at once unless it is closed.
+  /// 
+  /// Returns `Err(Closed)` without running the closure if the resource pool was closed
+  /// before the resources were aquired.
+  /// 
+  /// # Params
+  /// 
+  /// count --- The number of resources to aquire.  
+  /// f --- The closure to run once the resources are aquired.  
+  /// 
+  /// # Panics
+  /// 
+  /// If `count` is greater than the number of resources in this pool.
+  pub fn checked_get_resources<P, F,>(&self, count: usize, f: F,) -> Result<(), PoolError>
+    where P: Park,
+      F: FnOnce(&[usize], &mut [&mut R],), {
+    assert!(
+      count <= self.len(),
+      "cannot aquire `{}` of `{}` resources", count, self.len(),
+    );
 
-    impl<R, P,> Drop for Finish<'_, R, P,>
-      where R: Resource,
-        P: ReusePollicy<R,>, {
-      fn drop(&mut self,) {
-        let resource = unsafe { &mut self.pool.resources_mut()[self.resource] };
+    //Attempt to aquire the resources, waiting for a resource to be released between attempts.
+    self.id_queue.wait_with::<P, _, _, _,>(
+      self.id_waiting(), || self.attempt_aquire_many(count,),
+      || {},
+      self.pool_id(), Wait::Any(count,),
+    ).map(|finish,| self.run_many(finish, f,),)
+  }
+  /// A non blocking equivelant of `get_resources`.
+  /// 
+  /// Returns `true` if the closure was executed.
+  /// 
+  /// # Params
+  /// 
+  /// count --- The number of resources to aquire.  
+  /// f --- The closure to run once the resources are aquired.  
+  pub fn try_get_resources<F,>(&self, count: usize, f: F,) -> bool
+    where F: FnOnce(&[usize], &mut [&mut R],), {
+    //Attempt to aquire the resources.
+    match self.attempt_aquire_many(count,) {
+      Some(finish) => { self.run_many(finish, f,); true },
+      None => false,
+    }
+  }
+  /// Closes the resource pool and waits for every resource to be released.
+  /// 
+  /// Returns the resources in order of their Ids, leaving new instances in the resource
+  /// pool.
+  pub fn drain<P,>(&self,) -> Vec<R>
+    where P: Park, {
+    self.close();
+    //Attempt to take every resource, waiting for a resource to be released between attempts.
+    self.id_queue.wait::<P, _, _, _,>(
+      || {
+        //Take the resources while the available resources are locked.
+        self.available_resources.with(|available,| {
+          //Every resource is available so none are in use.
+          if available.len() != self.len() { return None }
 
-        //Check if we reuse the resource.
-        if !P::reuse(resource,) { *resource = R::new() }
+          //Only the instances are replaced so the number of resources never changes.
+          Some((0..self.len()).map(|id,| unsafe {
+            self.created[id].reset(self.timed(),);
+            mem::replace(&mut *self.resource(id,), R::new(),)
+          },).collect(),)
+        },)
+      },
+      || {},
+      self.pool_id(), Wait::Any(self.len(),),
+    )
+  }
+  /// Attempts to claim `count` resources.
+  /// 
+  /// Returns `None` if the resources could not all be taken.
+  /// 
+  /// # Params
+  /// 
+  /// count --- The number of resources to aquire.  
+  fn attempt_aquire_many(&self, count: usize,) -> Option<FinishMany<'_, R, Pol,>> {
+    //Aquire all of the resources or none of them.
+    let select = |resources: &mut Vec<usize>,| {
+      let available = resources.len();
 
-        let release = |_, resources: &mut Vec<usize>,| resources.push(self.resource,);
+      if available < count { None }
+      else { Some(resources.split_off(available - count,)) }
+    };
+    let resources = self.take_resource(select,)?;
 
-        //Release the resource.
-        while self.pool.available_resources.try_get_resource(release,) {
-          core::sync::atomic::spin_loop_hint();
-        }
-      }
-    }
+    self.stats.aquired();
 
-    let mut resource = None;
-    //Aquire a resource.
-    self.available_resources.try_get_resource(
-      |_, resources,| resource = resources.pop(),
-    );
+    Some(FinishMany { resources, timer: Timer::start_if(self.timed(),), pool: self, },)
+  }
+  /// Runs the closure with several claimed resources.
+  /// 
+  /// # Params
+  /// 
+  /// finish --- Releases the claimed resources.  
+  /// f --- The closure to run.  
+  fn run_many<F,>(&self, finish: FinishMany<'_, R, Pol,>, f: F,)
+    where F: FnOnce(&[usize], &mut [&mut R],), {
+    for &resource in finish.resources.iter() { debug_locks::aquired(self.pool_id(), resource,); }
 
-    match resource {
-      None => Some(f),
-      Some(resource) => {
-        let _ = Finish::<R, P,> { resource, pool: self, };
+    //The Ids are distinct so each reference is unique.
+    let mut resources = finish.resources.iter()
+      .map(|&resource,| unsafe { &mut *self.resource(resource,) },)
+      .collect::<Vec<_>>();
 
-        f(resource, unsafe { &mut self.resources_mut()[resource] },);
+    let _holding = trace::holding_many(self.pool_id(), &finish.resources,);
 
-        None
-      },
+    for (&id, resource,) in finish.resources.iter().zip(resources.iter_mut(),) {
+      self.hooks.on_acquire(id, resource,);
     }
+
+    f(&finish.resources, &mut resources,);
   }
 }
 
 impl<R, Pol,> MultiResource<R, Pol,> {
-  /// Gets the resources mutably.
+  /// Consumes the resource pool and returns the resources in order of their Ids.
   #[inline]
-  unsafe fn resources_mut(&self,) -> &mut Vec<R> {
-    &mut *(&self.resources as *const Vec<R> as *mut Vec<R>)
+  pub fn into_resources(self,) -> Vec<R> {
+    self.resources.into_iter().map(UnsafeCell::into_inner,).collect()
   }
-  /// Creates a new resource pool.
+  /// Gets the resource with Id `id` mutably.
   /// 
-  /// # Param
+  /// The exclusive borrow guarantees no resource is in use.
   /// 
-  /// resources --- The `Resource`s to use.  
+  /// # Params
+  /// 
+  /// id --- The Id of the resource.  
   #[inline]
-  pub fn with_resources(resources: Vec<R>,) -> Self {
-    Self {
-      available_resources: SingleResource::with_resource(
-        (0..resources.len()).collect(),
-      ),
-      resources,
-      sync_stack: SyncStack::new(),
-      _data: PhantomData,
-    }
+  pub fn get_mut(&mut self, id: usize,) -> Option<&mut R> {
+    self.resources.get_mut(id,).map(UnsafeCell::get_mut,)
   }
-}
+  /// Iterates over the resources mutably in order of their Ids.
+  /// 
+  /// The exclusive borrow guarantees no resource is in use.
+  #[inline]
+  pub fn iter_mut(&mut self,) -> core::slice::IterMut<'_, R,> {
+    //`UnsafeCell<R>` has the same layout as `R`.
+    let resources = self.resources.as_mut_slice() as *mut [UnsafeCell<R>] as *mut [R];
 
-unsafe impl<R,> ResourcePool for MultiResource<R, Reuse,>
-  where R: Resource, {
-  type Resource = R;
+    unsafe { &mut *resources }.iter_mut()
+  }
+  /// Replaces every resource in the resource pool.
+  /// 
+  /// The resources are given new Ids in order, their use counts start from `0` and their
+  /// ages from now.
+  /// Returns the previous resources in order of their Ids.
+  /// 
+  /// # Params
+  /// 
+  /// resources --- The new resources.  
+  pub fn replace_all(&mut self, resources: Vec<R>,) -> Vec<R> {
+    let count = resources.len();
 
+    *self.available_resources.get_mut() = (0..count).collect();
+    self.uses = (0..count).map(|_,| AtomicUsize::new(0,),).collect();
+    self.created = (0..count).map(|_,| Created::now(),).collect();
+
+    mem::replace(&mut self.resources, resources.into_iter().map(UnsafeCell::new,).collect(),)
+      .into_iter().map(UnsafeCell::into_inner,).collect()
+  }
+  /// Returns the number of resources in the resource pool.
   #[inline]
-  fn try_get_resource<F,>(&self, f: F,) -> bool
-    where F: FnOnce(usize, &mut Self::Resource,), {
-    //Attempt to aquire the resource.
-    let aquired = self.attempt_aquire(f,).is_none();
+  pub(crate) fn len(&self,) -> usize { self.resources.len() }
+  /// Gets a pointer to the resource with Id `id`.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The Id of the resource.  
+  #[inline]
+  pub(crate) fn resource(&self, id: usize,) -> *mut R { self.resources[id].get() }
+  /// Identifies this resource pool.
+  #[inline]
+  pub(crate) fn pool_id(&self,) -> PoolId {
+    PoolId::of(self, self.len(),).named(self.name,)
+  }
+  /// Removes resources from the available resources.
+  /// 
+  /// # Params
+  /// 
+  /// select --- Selects the resource to remove from the available resources.  
+  pub(crate) fn take_resource<S, T,>(&self, select: S,) -> Option<T>
+    where S: FnOnce(&mut Vec<usize>,) -> Option<T>, {
+    //A closed resource pool provides no resources.
+    if self.is_closed() { return None }
 
-    //If we aquired the resource allow another a chance to.
-    if aquired { self.sync_stack.pop(); }
+    //Lock the available resources.
+    self.available_resources.with(select,)
+  }
+  /// Returns a resource to the available resources.
+  /// 
+  /// # Params
+  /// 
+  /// resource --- The Id of the resource to release.  
+  pub(crate) fn release_resource(&self, resource: usize,) {
+    debug_locks::released(self.pool_id(), resource,);
+    self.offer(resource,);
+  }
+  /// Hands a resource to the waiting thread with the highest priority or returns it to the
+  /// available resources if no thread is waiting.
+  /// 
+  /// Handing the resource over stops threads which have not waited from taking it first.
+  /// 
+  /// # Params
+  /// 
+  /// resource --- The Id of the resource to offer.  
+  fn offer(&self, resource: usize,) {
+    //A closed resource pool hands out no resources.
+    if !self.is_closed() && self.queue.hand(resource,) { return }
 
-    aquired
+    //Release the resource.
+    self.available_resources.with(|resources,| resources.push(resource,),);
+    //Wake a thread which joined the queue after it was checked.
+    self.queue.pop();
+    //Wake all threads waiting for specific resources.
+    self.id_queue.pop_all();
   }
-  fn get_resource<P, F,>(&self, mut f: F,)
-    where P: Park,
-      F: FnOnce(usize, &mut Self::Resource,), {
-    loop {
-      //Attempt to aquire the resource.
-      match self.attempt_aquire(f,) {
-        //Failed to aquire the resource.
-        Some(ret) => {
-          //Return the resource for the next attempt.
-          f = ret;
-          //Wait for the resource to become available.
-          self.sync_stack.park::<P,>();
-        },
-        //We aquired the resource.
-        None => { self.sync_stack.pop(); break },
-      }
+  /// Passes on a wake up, and any resource handed with it, which a waiting thread
+  /// received after it had already aquired a resource.
+  /// 
+  /// # Params
+  /// 
+  /// handed --- The resource handed to the thread, if any.  
+  pub(crate) fn pass_on(&self, handed: Option<usize>,) {
+    match handed {
+      Some(resource) => self.offer(resource,),
+      None => { self.queue.pop(); },
     }
   }
-}
-
-unsafe impl<R,> ResourcePool for MultiResource<R, NoReuse,>
-  where R: Resource, {
-  type Resource = R;
-
+  /// Creates a new resource pool.
+  /// 
+  /// # Param
+  /// 
+  /// resources --- The `Resource`s to use.  
   #[inline]
-  fn try_get_resource<F,>(&self, f: F,) -> bool
-    where F: FnOnce(usize, &mut Self::Resource,), {
-    //Attempt to aquire the resource.
-    let aquired = self.attempt_aquire(f,).is_none();
-
-    //If we aquired the resource allow another a chance to.
-    if aquired { self.sync_stack.pop(); }
+  pub fn with_resources(resources: Vec<R>,) -> Self
+    where Pol: ConstPollicy, { Self::with_pollicy(resources, Pol::INIT,) }
+  /// Creates a new resource pool with a reuse pollicy.
+  /// 
+  /// # Param
+  /// 
+  /// resources --- The `Resource`s to use.  
+  /// pollicy --- The reuse pollicy to use.  
+  pub fn with_pollicy(resources: Vec<R>, pollicy: Pol,) -> Self {
+    Self {
+      available_resources: SpinLock::new((0..resources.len()).collect(),),
+      uses: resources.iter().map(|_,| AtomicUsize::new(0,),).collect(),
+      created: resources.iter().map(|_,| Created::now(),).collect(),
+      resources: resources.into_iter().map(UnsafeCell::new,).collect(),
+      queue: WaitQueue::new(),
+      id_queue: WaitQueue::new(),
+      max_waiters: usize::MAX,
+      closed: AtomicBool::new(false,),
+      pollicy,
+      hooks: Hooks::NONE,
+      name: None,
+      stats: PoolStats::new(),
+    }
+  }
+  /// Names the resource pool.
+  /// 
+  /// The name identifies the resource pool in the `registry`, in `tracing` output and in
+  /// deadlock reports.
+  /// 
+  /// # Params
+  /// 
+  /// name --- The name of the resource pool.  
+  #[inline]
+  pub const fn with_name(mut self, name: &'static str,) -> Self { self.name = Some(name,); self }
+  /// Returns the name of the resource pool.
+  #[inline]
+  pub fn name(&self,) -> Option<&'static str> { self.name }
+  /// Records how long threads wait for resources and how long they are held in the histograms
+  /// of `stats`.
+  #[inline]
+  pub const fn with_histograms(mut self,) -> Self {
+    self.stats = PoolStats::with_histograms();
+    self
+  }
+  /// Returns statistics about how the resources have been used.
+  #[inline]
+  pub fn stats(&self,) -> &PoolStats { &self.stats }
+  /// Returns the number of resources in use.
+  pub fn in_use(&self,) -> usize {
+    let available = self.available_resources.with(|resources,| resources.len(),);
 
-    aquired
+    self.len().saturating_sub(available,)
   }
-  fn get_resource<P, F,>(&self, mut f: F,)
-    where P: Park,
-      F: FnOnce(usize, &mut Self::Resource,), {
-    loop {
-      //Attempt to aquire the resource.
-      match self.attempt_aquire(f,) {
-        //Failed to aquire the resource.
-        Some(ret) => {
-          //Return the resource for the next attempt.
-          f = ret;
-          //Wait for the resource to become available.
-          self.sync_stack.park::<P,>();
-        },
-        //We aquired the resource.
-        None => { self.sync_stack.pop(); break },
-      }
-    }
+  /// Installs callbacks to run as the resources are used.
+  /// 
+  /// # Params
+  /// 
+  /// hooks --- The callbacks to run.  
+  pub const fn with_hooks(mut self, hooks: &'static (dyn PoolHooks<R,> + Sync),) -> Self
+    where R: 'static, {
+    self.hooks = Hooks::new(hooks,);
+    self
   }
+  /// Limits the number of threads which can wait for a resource at once.
+  /// 
+  /// Once `max` threads are waiting `checked_get_resource` and
+  /// `get_resource_with_priority` return `Err(QueueFull)` instead of waiting, so load can
+  /// be shed rather than accumulating blocked threads. `ResourcePool::get_resource`
+  /// panics with `QueueFull` instead.
+  /// 
+  /// # Params
+  /// 
+  /// max --- The maximum number of waiting threads.  
+  #[inline]
+  pub fn with_max_waiters(mut self, max: usize,) -> Self { self.max_waiters = max; self }
+  /// Describes how threads wait for any resource.
+  #[inline]
+  fn waiting(&self,) -> Waiting<'_,> {
+    Waiting { max: self.max_waiters.saturating_sub(self.id_queue.len(),), ..self.id_waiting() }
+  }
+  /// Describes how threads wait for specific resources or several resources at once.
+  #[inline]
+  fn id_waiting(&self,) -> Waiting<'_,> {
+    Waiting { closed: Some(&self.closed,), stats: Some(&self.stats,), ..Waiting::DEFAULT }
+  }
+  /// Closes the resource pool.
+  /// 
+  /// Every waiting thread is woken and fails to aquire a resource as does every later
+  /// aquisition; the fallible aquisitions return `Err(Closed)` and the others panic.
+  /// Threads already holding resources are unaffected.
+  pub fn close(&self,) {
+    self.closed.store(true, Ordering::SeqCst,);
+    self.queue.pop_all();
+    self.id_queue.pop_all();
+  }
+  /// Returns `true` if the resource pool has been closed.
+  #[inline]
+  pub fn is_closed(&self,) -> bool { self.closed.load(Ordering::SeqCst,) }
+  /// Returns the number of threads waiting on this resource pool.
+  #[inline]
+  pub fn waiters(&self,) -> usize { self.queue.len() + self.id_queue.len() }
 }
 
-unsafe impl<R, Pol,> ResourcePool for MultiResource<R, Pollicy<Pol,>,>
+unsafe impl<R, Pol,> ResourcePool for MultiResource<R, Pol,>
   where R: Resource,
-    Pol: ReusePollicy<R,>, {
+    Pol: PolicyKind<R,>, {
   type Resource = R;
 
   #[inline]
   fn try_get_resource<F,>(&self, f: F,) -> bool
     where F: FnOnce(usize, &mut Self::Resource,), {
     //Attempt to aquire the resource.
-    let aquired = self.attempt_aquire(f,).is_none();
-
-    //If we aquired the resource allow another a chance to.
-    if aquired { self.sync_stack.pop(); }
-
-    aquired
+    match self.attempt_aquire(Vec::pop,) {
+      Some(finish) => { self.run(finish, f,); true },
+      None => false,
+    }
   }
-  fn get_resource<P, F,>(&self, mut f: F,)
+  fn get_resource<P, F,>(&self, f: F,)
     where P: Park,
       F: FnOnce(usize, &mut Self::Resource,), {
-    loop {
-      //Attempt to aquire the resource.
-      match self.attempt_aquire(f,) {
-        //Failed to aquire the resource.
-        Some(ret) => {
-          //Return the resource for the next attempt.
-          f = ret;
-          //Wait for the resource to become available.
-          self.sync_stack.park::<P,>();
-        },
-        //We aquired the resource.
-        None => { self.sync_stack.pop(); break },
-      }
+    //Attempt to aquire a resource, waiting for a resource to be released between attempts.
+    let aquired = self.queue.wait_handed::<P, _, _, _, _,>(
+      self.waiting(), || self.attempt_aquire(Vec::pop,),
+      |resource,| self.claim(resource,),
+      |handed,| self.pass_on(handed,),
+      self.pool_id(), Wait::Any(1,),
+    );
+
+    match aquired {
+      Ok(finish) => self.run(finish, f,),
+      Err(error) => panic!("{}", error,),
     }
   }
 }
 
+impl<R, Pol,> Introspect for MultiResource<R, Pol,>
+  where Self: Sync, {
+  fn info(&self,) -> PoolInfo {
+    PoolInfo {
+      name: self.pool_id().name,
+      size: self.len(),
+      in_use: self.in_use(),
+      waiters: self.waiters(),
+    }
+  }
+  #[inline]
+  fn stats(&self,) -> Option<&PoolStats> { Some(&self.stats,) }
+}
+
 #[cfg(test,)]
 mod tests {
   use super::*;
@@ -318,12 +761,10 @@ mod tests {
   fn test_multi_resource_multithread() {
     use std::{thread, time::Duration,};
 
-    static mut RESOURCE: MultiResource<usize, Reuse,> = MultiResource::INIT;
+    static RESOURCE: LazyMultiResource<usize, Reuse,> = MultiResource::lazy(5, || 0,);
 
-    unsafe { RESOURCE = MultiResource::with_resources(vec![0; 5],); }
-    
     for _ in 0..10 {
-      thread::spawn(move || unsafe {
+      thread::spawn(move || {
         RESOURCE.get_resource::<Thread, _,>(|i, r,| {
           thread::sleep(Duration::from_millis(2000,),); *r = i;
         },)
@@ -331,11 +772,302 @@ mod tests {
     }
 
     for _ in 0..10 {
-      thread::spawn(move || unsafe {
+      thread::spawn(move || {
         RESOURCE.get_resource::<Thread, _,>(|i, r,| {
           thread::sleep(Duration::from_millis(400,),); assert_eq!(*r, i,)
         },)
       },);
     }
   }
+  #[test]
+  fn test_multi_resource_priority() {
+    use std::{thread, sync::{Arc, Mutex,},};
+
+    let resource = Arc::new(MultiResource::<usize, Reuse,>::new_resources(1,),);
+    let order = Arc::new(Mutex::new(Vec::new(),),);
+    let mut handles = Vec::new();
+
+    resource.get_resource::<Thread, _,>(|_, _,| {
+      for (waiter, &priority,) in [1, 0, 2, 1,].iter().enumerate() {
+        let (pool, order,) = (resource.clone(), order.clone(),);
+
+        handles.push(thread::spawn(move || {
+          pool.get_resource_with_priority::<Thread, _,>(priority, |_, _,| {
+            order.lock().unwrap().push(waiter,);
+          },).unwrap();
+        },),);
+        //Queue the waiters in order.
+        while resource.queue.len() <= waiter { thread::yield_now(); }
+      }
+    },);
+
+    for handle in handles { handle.join().unwrap(); }
+
+    //Highest priority first and first in first out within a priority.
+    assert_eq!(*order.lock().unwrap(), [2, 0, 3, 1,],);
+  }
+  #[test]
+  fn test_multi_resource_handoff() {
+    use std::{thread, sync::Barrier,};
+
+    let resource = MultiResource::<usize, Reuse,>::new_resources(1,);
+    let barrier = Barrier::new(2,);
+
+    thread::scope(|scope,| {
+      let mut waiter = None;
+
+      resource.get_resource::<Thread, _,>(|_, _,| {
+        waiter = Some(scope.spawn(|| {
+          resource.get_resource_with_priority::<Thread, _,>(1, |_, r,| {
+            *r += 1;
+            barrier.wait();
+          },)
+        },),);
+        while resource.queue.len() == 0 { thread::yield_now(); }
+      },);
+      //A low priority caller arriving as the resource is released cannot take it.
+      assert!(!resource.try_get_resource(|_, _,| (),),);
+      barrier.wait();
+      waiter.unwrap().join().unwrap().unwrap();
+    },);
+
+    assert!(resource.try_get_resource(|_, r,| assert_eq!(*r, 1,),),);
+  }
+  #[test]
+  fn test_multi_resource_max_waiters() {
+    use std::{thread, sync::Arc,};
+
+    let resource = Arc::new(
+      MultiResource::<usize, Reuse,>::new_resources(1,).with_max_waiters(1,),
+    );
+    let mut handle = None;
+
+    resource.get_resource::<Thread, _,>(|_, _,| {
+      let pool = resource.clone();
+
+      handle = Some(thread::spawn(move || pool.checked_get_resource::<Thread, _,>(|_, _,| {},),),);
+      while resource.waiters() == 0 { thread::yield_now(); }
+
+      //The queue is full.
+      assert_eq!(
+        resource.checked_get_resource::<Thread, _,>(|_, _,| unreachable!(),),
+        Err(PoolError::QueueFull),
+      );
+      //`get_resource` panics rather than waiting.
+      let pool = resource.clone();
+      assert!(thread::spawn(move || pool.get_resource::<Thread, _,>(|_, _,| unreachable!(),),).join().is_err(),);
+      assert_eq!(resource.waiters(), 1,);
+    },);
+
+    assert_eq!(handle.unwrap().join().unwrap(), Ok(()),);
+    assert_eq!(resource.waiters(), 0,);
+  }
+  #[test]
+  fn test_multi_resource_cancel() {
+    use std::{thread, sync::Arc,};
+
+    let resource = Arc::new(MultiResource::<usize, Reuse,>::new_resources(1,),);
+    let token = Arc::new(CancelToken::new(),);
+    let mut handle = None;
+
+    resource.get_resource::<Thread, _,>(|_, _,| {
+      let (pool, cancel,) = (resource.clone(), token.clone(),);
+
+      handle = Some(thread::spawn(move || {
+        pool.get_resource_cancellable::<Thread, _,>(&cancel, |_, _,| unreachable!(),)
+      },),);
+      while resource.waiters() == 0 { thread::yield_now(); }
+
+      token.cancel();
+    },);
+
+    assert_eq!(handle.unwrap().join().unwrap(), Err(PoolError::Cancelled),);
+    //The waiter left the queue.
+    assert_eq!(resource.waiters(), 0,);
+    assert_eq!(
+      resource.get_resource_cancellable::<Thread, _,>(&token, |_, _,| unreachable!(),),
+      Err(PoolError::Cancelled),
+    );
+  }
+  #[test]
+  fn test_multi_resource_close() {
+    use std::{thread, sync::Arc,};
+
+    let resource = Arc::new(MultiResource::<usize, Reuse,>::new_resources(2,),);
+    let mut drain = None;
+
+    resource.get_resource::<Thread, _,>(|id, r,| {
+      let pool = resource.clone();
+      let waiter = thread::spawn(move || {
+        pool.checked_get_resource_by_id::<Thread, _,>(id, |_, _,| unreachable!(),)
+      },);
+
+      //Both resources are in use.
+      assert!(resource.try_get_resource(|_, _,| {
+        while resource.waiters() == 0 { thread::yield_now(); }
+        resource.close();
+      },),);
+      //Waiting threads are woken.
+      assert_eq!(waiter.join().unwrap(), Err(PoolError::Closed),);
+
+      let pool = resource.clone();
+
+      //Draining waits for every resource to be released.
+      drain = Some(thread::spawn(move || pool.drain::<Thread>(),),);
+      *r = id + 1;
+    },);
+
+    //The resources are returned in order of their Ids.
+    let resources = drain.unwrap().join().unwrap();
+
+    assert!(resources == [1, 0,] || resources == [0, 2,],);
+    assert!(!resource.try_get_resource(|_, _,| unreachable!(),),);
+    assert_eq!(
+      resource.checked_get_resource::<Thread, _,>(|_, _,| unreachable!(),),
+      Err(PoolError::Closed),
+    );
+    assert_eq!(
+      resource.checked_get_resource_by_id::<Thread, _,>(0, |_, _,| unreachable!(),),
+      Err(PoolError::Closed),
+    );
+    assert_eq!(
+      resource.checked_get_resources::<Thread, _,>(2, |_, _,| unreachable!(),),
+      Err(PoolError::Closed),
+    );
+  }
+  #[test]
+  fn test_multi_resource_exclusive() {
+    let mut resource = MultiResource::<usize, Reuse,>::with_resources(vec![0, 1,],);
+
+    *resource.get_mut(1,).unwrap() = 2;
+    assert!(resource.get_mut(2,).is_none(),);
+    for r in resource.iter_mut() { *r += 1; }
+    assert_eq!(resource.replace_all(vec![3, 4, 5,],), [1, 3,],);
+    resource.get_resources::<Thread, _,>(3, |_, resources,| {
+      for resource in resources.iter_mut() { **resource += 1; }
+    },);
+    assert_eq!(resource.into_resources(), [4, 5, 6,],);
+  }
+  #[test]
+  fn test_multi_resource_hooks() {
+    use std::{sync::Mutex, time::Duration,};
+
+    /// Records the hooks which were run and the Id of the resource.
+    struct Log(Mutex<Vec<(&'static str, usize,)>>,);
+
+    impl PoolHooks<usize,> for Log {
+      fn on_acquire(&self, id: usize, _: &mut usize,) { self.0.lock().unwrap().push(("acquire", id,),) }
+      fn on_release(&self, id: usize, r: &mut usize, _: Duration,) {
+        assert_eq!(*r, 1,);
+        self.0.lock().unwrap().push(("release", id,),)
+      }
+      fn on_create(&self, id: usize, r: &mut usize,) {
+        assert_eq!(*r, 0,);
+        self.0.lock().unwrap().push(("create", id,),)
+      }
+      fn on_discard(&self, id: usize, _: &mut usize,) { self.0.lock().unwrap().push(("discard", id,),) }
+    }
+
+    static LOG: Log = Log(Mutex::new(Vec::new(),),);
+
+    let log = &LOG;
+    let resource = MultiResource::<usize, _,>::with_pollicy(vec![0; 2], Pollicy(MaxUses(1,),),)
+      .with_hooks(log,);
+
+    resource.get_resource_by_id::<Thread, _,>(1, |_, r,| *r += 1,);
+    assert_eq!(
+      *log.0.lock().unwrap(),
+      [("acquire", 1,), ("release", 1,), ("discard", 1,), ("create", 1,),],
+    );
+
+    log.0.lock().unwrap().clear();
+    resource.get_resources::<Thread, _,>(2, |_, resources,| {
+      for resource in resources.iter_mut() { **resource += 1; }
+    },);
+    assert_eq!(log.0.lock().unwrap().iter().filter(|(hook, _,),| *hook == "acquire",).count(), 2,);
+    assert_eq!(log.0.lock().unwrap().iter().filter(|(hook, _,),| *hook == "create",).count(), 2,);
+  }
+  #[test]
+  fn test_multi_resource_pollicy() {
+    /// Discards the resource with `Id` `1` after every use.
+    struct Odd;
+
+    impl ReusePollicy<usize,> for Odd {
+      fn reuse(&self, _: &mut usize, usage: &Usage,) -> bool {
+        usage.id != 1 && usage.uses <= 2
+      }
+    }
+
+    let resource = MultiResource::<usize, _,>::with_pollicy(vec![0; 2], Pollicy(Odd,),);
+
+    for _ in 0..2 {
+      resource.get_resources::<Thread, _,>(2, |_, resources,| {
+        for resource in resources.iter_mut() { **resource += 1; }
+      },);
+    }
+    resource.get_resource_by_id::<Thread, _,>(0, |_, r,| assert_eq!(*r, 2,),);
+    resource.get_resource_by_id::<Thread, _,>(0, |_, r,| assert_eq!(*r, 0,),);
+    resource.get_resource_by_id::<Thread, _,>(1, |_, r,| assert_eq!(*r, 0,),);
+  }
+  #[test]
+  fn test_multi_resource_by_id() {
+    use std::{thread, time::Duration, sync::Arc,};
+
+    let resource = Arc::new(MultiResource::<usize, Reuse,>::with_resources(vec![0; 3],),);
+
+    resource.get_resource_by_id::<Thread, _,>(1, |i, r,| { assert_eq!(i, 1,); *r = 1; },);
+    resource.get_resource_by_id::<Thread, _,>(1, |_, r,| assert_eq!(*r, 1,),);
+    resource.get_resource_by_id::<Thread, _,>(2, |_, r,| assert_eq!(*r, 0,),);
+    resource.get_resource::<Thread, _,>(|i, _,| {
+      assert!(!resource.try_get_resource_by_id(i, |_, _,| {},),);
+    },);
+
+    let handle = {
+      let resource = resource.clone();
+
+      thread::spawn(move || resource.get_resource_by_id::<Thread, _,>(0, |_, r,| {
+        thread::sleep(Duration::from_millis(400,),); *r = 2;
+      },),)
+    };
+
+    thread::sleep(Duration::from_millis(100,),);
+    resource.get_resource_by_id::<Thread, _,>(0, |_, r,| assert_eq!(*r, 2,),);
+    handle.join().unwrap();
+  }
+  #[test]
+  fn test_multi_resource_many() {
+    use std::{thread, sync::Arc,};
+
+    const THREADS: usize = 8;
+    const CYCLES: usize = 200;
+
+    let resource = Arc::new(MultiResource::<usize, Reuse,>::with_resources(vec![0; 4],),);
+
+    resource.get_resources::<Thread, _,>(4, |ids, resources,| {
+      assert_eq!(ids.len(), 4,);
+      for (&id, resource,) in ids.iter().zip(resources.iter_mut(),) { **resource = id; }
+    },);
+    resource.get_resources::<Thread, _,>(2, |ids, resources,| {
+      assert!(!resource.try_get_resources(3, |_, _,| {},),);
+      assert!(resource.try_get_resources(2, |_, _,| {
+        assert!(!resource.try_get_resources(1, |_, _,| {},),);
+      },),);
+      for (&id, resource,) in ids.iter().zip(resources.iter(),) { assert_eq!(**resource, id,); }
+    },);
+
+    let handles = (0..THREADS).map(|thread,| {
+      let resource = resource.clone();
+
+      thread::spawn(move || for _ in 0..CYCLES {
+        resource.get_resources::<Thread, _,>(2 + thread % 3, |ids, resources,| {
+          for (&id, resource,) in ids.iter().zip(resources.iter_mut(),) {
+            assert_eq!(**resource, id,); **resource = usize::MAX;
+          }
+          for (&id, resource,) in ids.iter().zip(resources.iter_mut(),) { **resource = id; }
+        },)
+      },)
+    },).collect::<Vec<_>>();
+
+    for handle in handles { handle.join().unwrap(); }
+  }
 }
@@ -0,0 +1,320 @@
+//! Renders the statistics of every registered resource pool in the OpenMetrics text
+//! format.
+//! 
+//! Every metric is labelled with the name of its resource pool so each registered resource
+//! pool should have a unique name. Resource pools which keep no statistics only report
+//! the gauges and only resource pools created `with_histograms` report the histograms.
+//! 
+//! ```rust
+//! use bottleneck::*;
+//! use std::thread::Thread;
+//! 
+//! static POOL: SingleResource<i32> = SingleResource::INIT.with_name("pool");
+//! 
+//! registry::register(&POOL);
+//! POOL.get_resource::<Thread, _>(|_, _,| {});
+//! 
+//! let metrics = openmetrics::render();
+//! 
+//! assert!(metrics.contains("bottleneck_acquisitions_total{pool=\"pool\"} 1\n"));
+//! assert!(metrics.ends_with("# EOF\n"));
+//! ```
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-18
+
+use crate::{PoolError, registry::{self, Introspect, PoolInfo,}, stats::{PoolStats, Histogram,},};
+use core::{fmt::{self, Write,}, time::Duration,};
+use std::{string::String, vec::Vec,};
+
+/// A metric family.
+struct Family {
+  /// The name of the metric family.
+  name: &'static str,
+  /// The type of the metric family.
+  kind: &'static str,
+  /// Describes the metric family.
+  help: &'static str,
+}
+
+/// Reads a gauge from the state of a resource pool.
+type Gauge = fn(&PoolInfo,) -> usize;
+/// Reads a counter from the statistics of a resource pool.
+type Counter = fn(&PoolStats,) -> usize;
+/// Reads a histogram from the statistics of a resource pool.
+type Durations = fn(&PoolStats,) -> &Histogram;
+
+/// The gauges reported for every resource pool.
+const GAUGES: [(Family, Gauge,); 3] = [
+  (
+    Family { name: "bottleneck_in_use", kind: "gauge", help: "Resources in use.", },
+    |info,| info.in_use,
+  ),
+  (
+    Family { name: "bottleneck_idle", kind: "gauge", help: "Resources not in use.", },
+    |info,| info.size.saturating_sub(info.in_use,),
+  ),
+  (
+    Family { name: "bottleneck_waiters", kind: "gauge", help: "Waiting threads.", },
+    |info,| info.waiters,
+  ),
+];
+/// The counters reported for every resource pool which keeps statistics.
+const COUNTERS: [(Family, Counter,); 2] = [
+  (
+    Family { name: "bottleneck_acquisitions", kind: "counter", help: "Aquisitions.", },
+    PoolStats::acquisitions,
+  ),
+  (
+    Family {
+      name: "bottleneck_recreations",
+      kind: "counter",
+      help: "Resources recreated by the reuse pollicy.",
+    },
+    PoolStats::recreations,
+  ),
+];
+/// The counter reported for every resource pool which keeps statistics, labelled with the
+/// reason the aquisitions failed.
+const FAILURES: Family = Family {
+  name: "bottleneck_failures",
+  kind: "counter",
+  help: "Aquisitions which stopped waiting without a resource.",
+};
+/// The histograms reported for every resource pool which records durations.
+const HISTOGRAMS: [(Family, Durations,); 2] = [
+  (
+    Family {
+      name: "bottleneck_wait_seconds",
+      kind: "histogram",
+      help: "Time spent waiting for resources.",
+    },
+    PoolStats::wait_time,
+  ),
+  (
+    Family {
+      name: "bottleneck_hold_seconds",
+      kind: "histogram",
+      help: "Time resources were held.",
+    },
+    PoolStats::hold_time,
+  ),
+];
+
+impl Family {
+  /// Writes the metadata of the metric family.
+  /// 
+  /// # Params
+  /// 
+  /// write --- The destination to write to.  
+  fn header<W,>(&self, write: &mut W,) -> fmt::Result
+    where W: Write, {
+    writeln!(write, "# TYPE {} {}", self.name, self.kind,)?;
+    writeln!(write, "# HELP {} {}", self.name, self.help,)
+  }
+}
+
+/// Escapes a label value.
+struct Label<'a,>(&'a str,);
+
+impl fmt::Display for Label<'_,> {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    for c in self.0.chars() {
+      match c {
+        '\\' => fmt.write_str("\\\\",)?,
+        '"' => fmt.write_str("\\\"",)?,
+        '\n' => fmt.write_str("\\n",)?,
+        c => fmt.write_char(c,)?,
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// Names the reason an aquisition failed.
+/// 
+/// # Params
+/// 
+/// error --- The reason the aquisition failed.  
+fn reason(error: PoolError,) -> &'static str {
+  match error {
+    PoolError::QueueFull => "queue_full",
+    PoolError::Cancelled => "cancelled",
+    PoolError::Closed => "closed",
+  }
+}
+
+/// Formats a duration in seconds.
+struct Seconds(Duration,);
+
+impl fmt::Display for Seconds {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    write!(fmt, "{}", self.0.as_secs_f64(),)
+  }
+}
+
+/// Writes the statistics of every registered resource pool in the OpenMetrics text format.
+/// 
+/// # Params
+/// 
+/// write --- The destination to write to.  
+#[inline]
+pub fn write<W,>(write: &mut W,) -> fmt::Result
+  where W: Write, { write_pools(write, &registry::registered(),) }
+
+/// Writes the statistics of resource pools in the OpenMetrics text format.
+/// 
+/// # Params
+/// 
+/// write --- The destination to write to.  
+/// pools --- The resource pools to describe.  
+fn write_pools<W,>(write: &mut W, pools: &[&dyn Introspect],) -> fmt::Result
+  where W: Write, {
+  let pools = pools.iter()
+    .map(|&pool,| (pool.info(), pool,),)
+    .collect::<Vec<_>>();
+
+  for (family, gauge,) in GAUGES.iter() {
+    family.header(write,)?;
+    for (info, _,) in pools.iter() {
+      writeln!(
+        write, "{}{{pool=\"{}\"}} {}",
+        family.name, Label(info.name,), gauge(info,),
+      )?;
+    }
+  }
+  for (family, counter,) in COUNTERS.iter() {
+    family.header(write,)?;
+    for (info, pool,) in pools.iter() {
+      if let Some(stats) = pool.stats() {
+        writeln!(
+          write, "{}_total{{pool=\"{}\"}} {}",
+          family.name, Label(info.name,), counter(stats,),
+        )?;
+      }
+    }
+  }
+  FAILURES.header(write,)?;
+  for (info, pool,) in pools.iter() {
+    if let Some(stats) = pool.stats() {
+      for &error in PoolError::ALL.iter() {
+        writeln!(
+          write, "{}_total{{pool=\"{}\",reason=\"{}\"}} {}",
+          FAILURES.name, Label(info.name,), reason(error,), stats.failures(error,),
+        )?;
+      }
+    }
+  }
+
+  for (family, durations,) in HISTOGRAMS.iter() {
+    family.header(write,)?;
+    for (info, pool,) in pools.iter() {
+      let histogram = match pool.stats() {
+        Some(stats) if stats.has_histograms() => durations(stats,).snapshot(),
+        _ => continue,
+      };
+      let name = Label(info.name,);
+      let mut count = 0;
+
+      //Bucket counts are cumulative.
+      for (bucket, &value,) in histogram.buckets().iter().enumerate() {
+        count += value;
+        match Histogram::bound(bucket,) {
+          Some(bound) => writeln!(
+            write, "{}_bucket{{pool=\"{}\",le=\"{}\"}} {}",
+            family.name, name, Seconds(bound,), count,
+          )?,
+          None => writeln!(
+            write, "{}_bucket{{pool=\"{}\",le=\"+Inf\"}} {}", family.name, name, count,
+          )?,
+        }
+      }
+      writeln!(
+        write, "{}_sum{{pool=\"{}\"}} {}", family.name, name, Seconds(histogram.sum(),),
+      )?;
+      writeln!(write, "{}_count{{pool=\"{}\"}} {}", family.name, name, count,)?;
+    }
+  }
+
+  writeln!(write, "# EOF",)
+}
+
+/// Renders the statistics of every registered resource pool in the OpenMetrics text
+/// format.
+pub fn render() -> String {
+  let mut metrics = String::new();
+
+  write(&mut metrics,).expect("writing to a `String` cannot fail",);
+  metrics
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{*, pollicy::NoReuse,};
+  use std::thread::{self, Thread,};
+
+  #[test]
+  fn test_openmetrics() {
+    let single = SingleResource::<i32, NoReuse,>::new().with_name("single \"a\"",);
+    let multi = MultiResource::<i32,>::new_resources(2,)
+      .with_name("multi",)
+      .with_histograms();
+    let mut metrics = String::new();
+    let token = CancelToken::new();
+
+    token.cancel();
+    single.get_resource::<Thread, _,>(|_, _,| {},);
+    assert_eq!(
+      single.get_resource_cancellable::<Thread, _,>(&token, |_, _,| unreachable!(),),
+      Err(PoolError::Cancelled),
+    );
+    thread::scope(|scope,| multi.get_resource::<Thread, _,>(|_, _,| {
+      multi.get_resource::<Thread, _,>(|_, _,| {
+        scope.spawn(|| multi.get_resource::<Thread, _,>(|_, _,| {},),);
+
+        while multi.waiters() == 0 { thread::yield_now(); }
+        write_pools(&mut metrics, &[&single, &multi,],).unwrap();
+      },);
+    },),);
+    //Waits are recorded as resources are claimed and holds once they are released.
+    assert_eq!(multi.stats().wait_time().count(), 3,);
+    assert_eq!(multi.stats().hold_time().count(), 3,);
+    assert_eq!(single.stats().wait_time().count(), 0,);
+
+    let lines = metrics.lines().collect::<Vec<_>>();
+
+    assert_eq!(
+      lines[..8],
+      [
+        "# TYPE bottleneck_in_use gauge",
+        "# HELP bottleneck_in_use Resources in use.",
+        "bottleneck_in_use{pool=\"single \\\"a\\\"\"} 0",
+        "bottleneck_in_use{pool=\"multi\"} 2",
+        "# TYPE bottleneck_idle gauge",
+        "# HELP bottleneck_idle Resources not in use.",
+        "bottleneck_idle{pool=\"single \\\"a\\\"\"} 1",
+        "bottleneck_idle{pool=\"multi\"} 0",
+      ],
+    );
+    assert!(lines.contains(&"bottleneck_waiters{pool=\"multi\"} 1",),);
+    assert!(lines.contains(&"bottleneck_acquisitions_total{pool=\"multi\"} 2",),);
+    assert!(lines.contains(&"bottleneck_recreations_total{pool=\"single \\\"a\\\"\"} 1",),);
+    assert!(lines.contains(
+      &"bottleneck_failures_total{pool=\"single \\\"a\\\"\",reason=\"cancelled\"} 1",
+    ),);
+    assert!(lines.contains(&"bottleneck_failures_total{pool=\"multi\",reason=\"closed\"} 0",),);
+    for &line in [
+      "bottleneck_wait_seconds_bucket{pool=\"multi\",le=\"0.000001\"} 2",
+      "bottleneck_wait_seconds_bucket{pool=\"multi\",le=\"+Inf\"} 2",
+      "bottleneck_wait_seconds_count{pool=\"multi\"} 2",
+      "bottleneck_hold_seconds_count{pool=\"multi\"} 0",
+    ].iter() {
+      assert!(lines.contains(&line,), "missing {}", line,);
+    }
+    //Resource pools without histograms do not report them.
+    assert!(!lines.iter().any(|line,| line.contains("_seconds_count{pool=\"single",),),);
+    assert_eq!(lines.last(), Some(&"# EOF",),);
+  }
+}
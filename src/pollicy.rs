@@ -1,11 +1,31 @@
 //! Defines the different kinds of reuse policies for resource pools.
 //! 
 //! Author --- daniel.bechaz@gmail.com  
-//! Last Moddified --- 2019-06-14
+//! Last Moddified --- 2026-10-18
 
-use core::marker::PhantomData;
+use crate::Reset;
+use core::{mem, time::Duration, cell::UnsafeCell,};
+use alloc::vec::Vec;
 
 /// A trait which defines a reuse pollicy.
+/// 
+/// ```rust
+/// use bottleneck::{*, pollicy::*,};
+/// use std::thread::Thread;
+/// 
+/// /// Discards a resource instance after it has been used `self.0` times.
+/// struct Uses(usize,);
+/// 
+/// impl<R,> ReusePollicy<R,> for Uses {
+///   fn reuse(&self, _: &mut R, usage: &Usage,) -> bool { usage.uses < self.0 }
+/// }
+/// 
+/// let pool = SingleResource::<i32, _,>::with_pollicy(0, Pollicy(Uses(2,),),);
+/// 
+/// pool.get_resource::<Thread, _,>(|_, resource,| *resource = 1,);
+/// pool.get_resource::<Thread, _,>(|_, resource,| assert_eq!(*resource, 1,),);
+/// pool.get_resource::<Thread, _,>(|_, resource,| assert_eq!(*resource, 0,),);
+/// ```
 pub trait ReusePollicy<R,> {
   /// Returns `true` if `resource` should be reused.
   /// 
@@ -15,19 +35,192 @@ pub trait ReusePollicy<R,> {
   /// 
   /// # Params
   /// 
-  /// resource --- The resource instance which was just released.
-  fn reuse(resource: &mut R,) -> bool;
+  /// resource --- The resource instance which was just released.  
+  /// usage --- How the resource instance has been used.  
+  fn reuse(&self, resource: &mut R, usage: &Usage,) -> bool;
+  /// Returns `true` if this pollicy reads `Usage::held` or `Usage::age`.
+  /// 
+  /// If `false` resource pools with no hooks or histograms do not read the clock and the
+  /// durations in `Usage` may be zero.
+  #[inline]
+  fn timed(&self,) -> bool { true }
+}
+
+/// Describes how a resource instance has been used when it is released.
+#[derive(Clone, Copy, PartialEq, Eq, Debug,)]
+pub struct Usage {
+  /// The `Id` of the resource in its resource pool.
+  pub id: usize,
+  /// The number of times the resource instance has been used, including this use.
+  /// 
+  /// This is reset when the resource instance is discarded.
+  pub uses: usize,
+  /// How long the resource instance was held for this use.
+  /// 
+  /// Without the `std` feature there is no clock and this is always zero.
+  pub held: Duration,
+  /// How long ago the resource instance was created.
+  /// 
+  /// A resource instance created in a `const`, such as the resource of a `static`, is
+  /// aged from when it was first aquired. Without the `std` feature there is no clock and
+  /// this is always zero.
+  pub age: Duration,
+}
+
+/// Measures how long a resource is held for.
+#[derive(Clone, Copy,)]
+pub(crate) struct Timer(#[cfg(feature = "std",)] Option<std::time::Instant>,);
+
+impl Timer {
+  /// Starts timing.
+  #[inline]
+  pub fn start() -> Self {
+    Timer(#[cfg(feature = "std",)] Some(std::time::Instant::now(),),)
+  }
+  /// Starts timing only if `timed` is `true`, a timer which was not started measures
+  /// zero without reading the clock.
+  /// 
+  /// # Params
+  /// 
+  /// timed --- If the elapsed time will be used.  
+  #[inline]
+  pub fn start_if(timed: bool,) -> Self {
+    if timed { Self::start() } else { Timer(#[cfg(feature = "std",)] None,) }
+  }
+  /// Returns the time since the timer was started.
+  #[inline]
+  pub fn elapsed(&self,) -> Duration {
+    #[cfg(feature = "std",)] { self.0.map_or(Duration::from_secs(0,), |start,| start.elapsed(),) }
+    #[cfg(not(feature = "std",),)] { Duration::from_secs(0,) }
+  }
+}
+
+/// Records when a resource instance was created.
+pub(crate) struct Created(UnsafeCell<Option<Timer>>,);
+
+unsafe impl Sync for Created {}
+
+impl Created {
+  /// Records a resource instance created before the clock could be read.
+  /// 
+  /// Its age is measured from the start of its first hold.
+  #[inline]
+  pub const fn new() -> Self { Created(UnsafeCell::new(None,),) }
+  /// Records a resource instance created now.
+  #[inline]
+  pub fn now() -> Self { Created(UnsafeCell::new(Some(Timer::start(),),),) }
+  /// Returns the age of the resource instance.
+  /// 
+  /// # Params
+  /// 
+  /// timer --- Timed the hold which just ended.  
+  /// 
+  /// # Safety
+  /// 
+  /// The resource instance must be held by the current thread.
+  #[inline]
+  pub unsafe fn age(&self, timer: Timer,) -> Duration {
+    (*self.0.get()).get_or_insert(timer,).elapsed()
+  }
+  /// Records that the resource instance was just replaced.
+  /// 
+  /// # Params
+  /// 
+  /// timed --- If the age of the resource instance will be used.  
+  /// 
+  /// # Safety
+  /// 
+  /// The resource instance must be held by the current thread.
+  #[inline]
+  pub unsafe fn reset(&self, timed: bool,) { *self.0.get() = Some(Timer::start_if(timed,),) }
+}
+
+/// The kinds of pollicy a resource pool can use.
+/// 
+/// This trait is sealed and implemented by `Reuse`, `NoReuse` and `Pollicy`; custom
+/// reuse pollicies are used by implementing `ReusePollicy` and wrapping them in `Pollicy`.
+pub trait PolicyKind<R,>: sealed::Sealed {
+  /// If `true` a `SingleResource` gives each caller the resource instance and replaces
+  /// it immediately rather than holding the resource while it is in use.
+  const DISCARD: bool;
+
+  /// Returns `true` if `resource` should be reused.
+  /// 
+  /// # Params
+  /// 
+  /// resource --- The resource instance which was just released.  
+  /// usage --- How the resource instance has been used.  
+  fn reuse(&self, resource: &mut R, usage: &Usage,) -> bool;
+  /// Returns `true` if the pollicy reads `Usage::held` or `Usage::age`.
+  fn timed(&self,) -> bool;
+}
+
+mod sealed {
+  use super::*;
+
+  pub trait Sealed {}
+
+  impl Sealed for Reuse {}
+  impl Sealed for NoReuse {}
+  impl<P,> Sealed for Pollicy<P,> {}
+}
+
+impl<R,> PolicyKind<R,> for Reuse {
+  const DISCARD: bool = false;
+
+  #[inline]
+  fn reuse(&self, _: &mut R, _: &Usage,) -> bool { true }
+  #[inline]
+  fn timed(&self,) -> bool { false }
+}
+
+impl<R,> PolicyKind<R,> for NoReuse {
+  const DISCARD: bool = true;
+
+  #[inline]
+  fn reuse(&self, _: &mut R, _: &Usage,) -> bool { false }
+  #[inline]
+  fn timed(&self,) -> bool { false }
+}
+
+impl<R, P,> PolicyKind<R,> for Pollicy<P,>
+  where P: ReusePollicy<R,>, {
+  const DISCARD: bool = false;
+
+  #[inline]
+  fn reuse(&self, resource: &mut R, usage: &Usage,) -> bool { self.0.reuse(resource, usage,) }
+  #[inline]
+  fn timed(&self,) -> bool { self.0.timed() }
+}
+
+/// A pollicy with a constant inital value.
+pub trait ConstPollicy {
+  /// The inital value.
+  const INIT: Self;
 }
 
 /// A pollicy which makes the decision to reuse or discard a resource instance at runtime.
-pub struct Pollicy<P,>(PhantomData<P>,);
+/// 
+/// The `ReusePollicy` is stored in the resource pool.
+#[derive(Clone, Copy,)]
+pub struct Pollicy<P,>(pub P,);
+
+impl<P,> ConstPollicy for Pollicy<P,>
+  where P: ConstPollicy, {
+  const INIT: Self = Pollicy(P::INIT,);
+}
 
 /// A flag to indicate that a resource pool should reuse resource instances.
 /// 
 /// This is the strictest pollicy which will spend the minimum amount of time producing
 /// resource instances.
+#[derive(Clone, Copy,)]
 pub struct Reuse;
 
+impl ConstPollicy for Reuse {
+  const INIT: Self = Reuse;
+}
+
 /// A flag to indicate that a resource pool should not reuse resource instances.
 /// 
 /// This is the loosest pollicy which also provides the highest concurrency; since no
@@ -37,4 +230,291 @@ pub struct Reuse;
 /// pollicy provides higher concurrency than a `MultiResource` pool using the same
 /// pollicy by producing new resource instances as needed while a `MultiResource` will
 /// always limit the number of threads which can access the pool at once.
+#[derive(Clone, Copy,)]
 pub struct NoReuse;
+
+impl ConstPollicy for NoReuse {
+  const INIT: Self = NoReuse;
+}
+
+/// A trait for resources which know how many bytes they occupy.
+/// 
+/// Types which own no heap memory can implement this without any methods.
+pub trait Size {
+  /// Returns the number of bytes this resource instance occupies, including any memory
+  /// it has allocated.
+  #[inline]
+  fn size(&self,) -> usize { mem::size_of_val(self,) + self.heap_size() }
+  /// Returns the number of bytes this resource instance has allocated on the heap.
+  #[inline]
+  fn heap_size(&self,) -> usize { 0 }
+}
+
+/// A reuse pollicy which discards a resource instance after it has been used `self.0`
+/// times.
+#[derive(Clone, Copy,)]
+pub struct MaxUses(pub usize,);
+
+impl<R,> ReusePollicy<R,> for MaxUses {
+  #[inline]
+  fn reuse(&self, _: &mut R, usage: &Usage,) -> bool { usage.uses < self.0 }
+  #[inline]
+  fn timed(&self,) -> bool { false }
+}
+
+/// A reuse pollicy which discards a resource instance if the thread which just released
+/// it held it for longer than `self.0`.
+/// 
+/// Only the most recent hold is measured, `MaxAge` measures how long ago the resource
+/// instance was created. Without the `std` feature hold times are always zero and no
+/// resource instance is discarded.
+#[derive(Clone, Copy,)]
+pub struct MaxHold(pub Duration,);
+
+impl<R,> ReusePollicy<R,> for MaxHold {
+  #[inline]
+  fn reuse(&self, _: &mut R, usage: &Usage,) -> bool { usage.held <= self.0 }
+}
+
+/// A reuse pollicy which discards a resource instance once it was created more than
+/// `self.0` ago.
+/// 
+/// The age is checked when the resource instance is released. Without the `std` feature
+/// ages are always zero and no resource instance is discarded.
+#[derive(Clone, Copy,)]
+pub struct MaxAge(pub Duration,);
+
+impl<R,> ReusePollicy<R,> for MaxAge {
+  #[inline]
+  fn reuse(&self, _: &mut R, usage: &Usage,) -> bool { usage.age <= self.0 }
+}
+
+/// A reuse pollicy which resets a resource instance with `self.0` and always reuses it.
+#[derive(Clone, Copy,)]
+pub struct ResetWith<F,>(pub F,);
+
+impl<R, F,> ReusePollicy<R,> for ResetWith<F,>
+  where F: Fn(&mut R,), {
+  #[inline]
+  fn reuse(&self, resource: &mut R, _: &Usage,) -> bool { (self.0)(resource,); true }
+  #[inline]
+  fn timed(&self,) -> bool { false }
+}
+
+/// A reuse pollicy which resets a resource instance in place and always reuses it.
+/// 
+/// Unlike `NoReuse` any memory allocated by the resource instance is kept.
+#[derive(Clone, Copy,)]
+pub struct ResetReuse;
+
+impl ConstPollicy for ResetReuse {
+  const INIT: Self = ResetReuse;
+}
+
+impl<R,> ReusePollicy<R,> for ResetReuse
+  where R: Reset, {
+  #[inline]
+  fn reuse(&self, resource: &mut R, _: &Usage,) -> bool { resource.reset(); true }
+  #[inline]
+  fn timed(&self,) -> bool { false }
+}
+
+/// A reuse pollicy which clears a `Vec` and reuses its capacity.
+/// 
+/// A `Vec` with a capacity of more than `self.0` elements is discarded instead.
+#[derive(Clone, Copy,)]
+pub struct ClearVec(pub usize,);
+
+impl<T,> ReusePollicy<Vec<T>,> for ClearVec {
+  #[inline]
+  fn reuse(&self, resource: &mut Vec<T>, _: &Usage,) -> bool {
+    resource.clear();
+
+    resource.capacity() <= self.0
+  }
+  #[inline]
+  fn timed(&self,) -> bool { false }
+}
+
+/// A reuse pollicy which discards a resource instance which occupies more than `self.0`
+/// bytes.
+#[derive(Clone, Copy,)]
+pub struct DiscardIfLarger(pub usize,);
+
+impl<R,> ReusePollicy<R,> for DiscardIfLarger
+  where R: Size, {
+  #[inline]
+  fn reuse(&self, resource: &mut R, _: &Usage,) -> bool { resource.size() <= self.0 }
+  #[inline]
+  fn timed(&self,) -> bool { false }
+}
+
+/// A reuse pollicy which reuses a resource instance only if both pollicies do.
+/// 
+/// `self.1` is not checked if `self.0` discards the resource instance.
+#[derive(Clone, Copy,)]
+pub struct And<A, B,>(pub A, pub B,);
+
+impl<R, A, B,> ReusePollicy<R,> for And<A, B,>
+  where A: ReusePollicy<R,>,
+    B: ReusePollicy<R,>, {
+  #[inline]
+  fn reuse(&self, resource: &mut R, usage: &Usage,) -> bool {
+    self.0.reuse(resource, usage,) && self.1.reuse(resource, usage,)
+  }
+  #[inline]
+  fn timed(&self,) -> bool { self.0.timed() || self.1.timed() }
+}
+
+/// A reuse pollicy which reuses a resource instance if either pollicy does.
+/// 
+/// `self.1` is not checked if `self.0` reuses the resource instance.
+#[derive(Clone, Copy,)]
+pub struct Or<A, B,>(pub A, pub B,);
+
+impl<R, A, B,> ReusePollicy<R,> for Or<A, B,>
+  where A: ReusePollicy<R,>,
+    B: ReusePollicy<R,>, {
+  #[inline]
+  fn reuse(&self, resource: &mut R, usage: &Usage,) -> bool {
+    self.0.reuse(resource, usage,) || self.1.reuse(resource, usage,)
+  }
+  #[inline]
+  fn timed(&self,) -> bool { self.0.timed() || self.1.timed() }
+}
+
+/// A reuse pollicy which reuses a resource instance only if `self.0` does not.
+#[derive(Clone, Copy,)]
+pub struct Not<P,>(pub P,);
+
+impl<R, P,> ReusePollicy<R,> for Not<P,>
+  where P: ReusePollicy<R,>, {
+  #[inline]
+  fn reuse(&self, resource: &mut R, usage: &Usage,) -> bool { !self.0.reuse(resource, usage,) }
+  #[inline]
+  fn timed(&self,) -> bool { self.0.timed() }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::*;
+  use alloc::vec;
+  use std::thread::Thread;
+
+  #[test]
+  fn test_pollicy_single() {
+    let pollicy = And(MaxUses(3,), ClearVec(4,),);
+    let resource = SingleResource::<Vec<u8>, _,>::with_pollicy(Vec::new(), Pollicy(pollicy,),);
+
+    resource.get_resource::<Thread, _,>(|_, r,| { r.reserve_exact(2,); r.push(1,); },);
+    resource.get_resource::<Thread, _,>(|_, r,| {
+      //The `Vec` was cleared and kept its capacity.
+      assert!(r.is_empty(),);
+      assert!(r.capacity() >= 2,);
+      r.reserve_exact(8,);
+    },);
+    resource.get_resource::<Thread, _,>(|_, r,| assert_eq!(r.capacity(), 0,),);
+
+    let pollicy = Or(Not(MaxUses(2,),), ResetWith(|r: &mut i32,| *r += 1,),);
+    let resource = SingleResource::<i32, _,>::with_pollicy(0, Pollicy(pollicy,),);
+
+    resource.get_resource::<Thread, _,>(|_, r,| assert_eq!(*r, 0,),);
+    resource.get_resource::<Thread, _,>(|_, r,| assert_eq!(*r, 1,),);
+    //The second use was reused by `Not` without being reset.
+    resource.get_resource::<Thread, _,>(|_, r,| assert_eq!(*r, 1,),);
+  }
+  #[test]
+  fn test_pollicy_multi() {
+    let pollicy = Or(MaxHold(Duration::from_secs(60,),), DiscardIfLarger(0,),);
+    let resource = MultiResource::<Vec<u64>, _,>::with_pollicy(
+      vec![Vec::new(), Vec::new(),], Pollicy(pollicy,),
+    );
+
+    resource.get_resources::<Thread, _,>(2, |_, resources,| {
+      for resource in resources.iter_mut() { resource.push(1,); }
+    },);
+    resource.get_resources::<Thread, _,>(2, |_, resources,| {
+      for resource in resources.iter() { assert_eq!(**resource, [1],); }
+    },);
+
+    let resource = MultiResource::<Vec<u64>, _,>::with_pollicy(
+      vec![Vec::new(), Vec::new(),], Pollicy(DiscardIfLarger(0,),),
+    );
+
+    resource.get_resource_by_id::<Thread, _,>(1, |_, r,| r.push(1,),);
+    resource.get_resource_by_id::<Thread, _,>(1, |_, r,| assert!(r.is_empty(),),);
+  }
+  #[cfg(feature = "std",)]
+  #[test]
+  fn test_pollicy_max_hold() {
+    let pollicy = MaxHold(Duration::from_millis(100,),);
+    let resource = SingleResource::<i32, _,>::with_pollicy(0, Pollicy(pollicy,),);
+
+    resource.get_resource::<Thread, _,>(|_, r,| *r = 1,);
+    //A brief hold keeps the instance.
+    resource.get_resource::<Thread, _,>(|_, r,| {
+      assert_eq!(*r, 1,);
+      std::thread::sleep(Duration::from_millis(150,),);
+    },);
+    //A long hold discards the instance.
+    resource.get_resource::<Thread, _,>(|_, r,| assert_eq!(*r, 0,),);
+  }
+  #[cfg(feature = "std",)]
+  #[test]
+  fn test_pollicy_max_age() {
+    use std::thread;
+
+    let pollicy = MaxAge(Duration::from_millis(100,),);
+    let resource = MultiResource::<i32, _,>::with_pollicy(vec![0,], Pollicy(pollicy,),);
+
+    resource.get_resource::<Thread, _,>(|_, r,| *r = 1,);
+    thread::sleep(Duration::from_millis(60,),);
+    //Brief holds of a young instance keep it.
+    resource.get_resource::<Thread, _,>(|_, r,| { assert_eq!(*r, 1,); *r = 2; },);
+    thread::sleep(Duration::from_millis(60,),);
+    //The instance is discarded once it is old, however briefly it was held.
+    resource.get_resource::<Thread, _,>(|_, r,| assert_eq!(*r, 2,),);
+    resource.get_resource::<Thread, _,>(|_, r,| { assert_eq!(*r, 0,); *r = 3; },);
+    //The new instance is young again.
+    resource.get_resource::<Thread, _,>(|_, r,| assert_eq!(*r, 3,),);
+  }
+  #[cfg(feature = "std",)]
+  #[test]
+  fn test_pollicy_timed() {
+    /// Checks that the hold was only timed if the pollicy asked for it.
+    struct Timed(bool,);
+
+    impl ReusePollicy<i32,> for Timed {
+      fn reuse(&self, _: &mut i32, usage: &Usage,) -> bool {
+        assert_eq!(usage.held.as_nanos() != 0, self.0,);
+        true
+      }
+      fn timed(&self,) -> bool { self.0 }
+    }
+
+    for &timed in [false, true,].iter() {
+      let resource = SingleResource::<i32, _,>::with_pollicy(0, Pollicy(Timed(timed,),),);
+
+      resource.get_resource::<Thread, _,>(|_, _,| std::thread::sleep(Duration::from_millis(1,),),);
+    }
+  }
+  #[test]
+  fn test_pollicy_reset() {
+    let resource = SingleResource::<i32, Pollicy<ResetReuse,>,>::new();
+
+    resource.get_resource::<Thread, _,>(|_, r,| *r = 1,);
+    resource.get_resource::<Thread, _,>(|_, r,| assert_eq!(*r, 0,),);
+
+    let resource = MultiResource::<Vec<u8>, Pollicy<ResetReuse,>,>::new_resources(2,);
+
+    resource.get_resources::<Thread, _,>(2, |_, resources,| {
+      for resource in resources.iter_mut() { resource.reserve_exact(8,); resource.push(1,); }
+    },);
+    resource.get_resources::<Thread, _,>(2, |_, resources,| for resource in resources.iter() {
+      //The `Vec` was cleared without being reallocated.
+      assert!(resource.is_empty(),);
+      assert!(resource.capacity() >= 8,);
+    },);
+  }
+}
@@ -0,0 +1,166 @@
+//! A process-wide registry of resource pools for diagnostics.
+//! 
+//! Resource pools which live for the rest of the program, such as `static`s, can be
+//! registered so a diagnostic endpoint or a panic hook can describe every resource pool
+//! at once.
+//! 
+//! ```rust
+//! use bottleneck::{*, registry::PoolInfo,};
+//! 
+//! static BUFFERS: MultiResource<Vec<u8>> = MultiResource::INIT.with_name("buffers");
+//! 
+//! registry::register(&BUFFERS);
+//! 
+//! assert_eq!(
+//!   registry::pools(),
+//!   [PoolInfo { name: "buffers", size: 0, in_use: 0, waiters: 0, }],
+//! );
+//! ```
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-18
+
+use crate::{stats::PoolStats, spin_lock::SpinLock,};
+use core::fmt;
+use alloc::vec::Vec;
+
+/// A snapshot of the state of a resource pool.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub struct PoolInfo {
+  /// The name of the resource pool, or its type name if it is unnamed.
+  pub name: &'static str,
+  /// The number of resources in the resource pool.
+  pub size: usize,
+  /// The number of resources in use.
+  pub in_use: usize,
+  /// The number of threads waiting on the resource pool.
+  pub waiters: usize,
+}
+
+impl fmt::Display for PoolInfo {
+  fn fmt(&self, fmt: &mut fmt::Formatter,) -> fmt::Result {
+    write!(
+      fmt, "{}: size={} in_use={} waiters={}",
+      self.name, self.size, self.in_use, self.waiters,
+    )
+  }
+}
+
+/// A resource pool which can describe its state.
+pub trait Introspect: Sync {
+  /// Describes the current state of the resource pool.
+  fn info(&self,) -> PoolInfo;
+  /// Returns the statistics kept by the resource pool, if any.
+  #[inline]
+  fn stats(&self,) -> Option<&PoolStats> { None }
+}
+
+/// The registered resource pools in order of registration.
+static POOLS: SpinLock<Vec<&'static dyn Introspect>> = SpinLock::new(Vec::new(),);
+
+/// Runs the closure with the registered resource pools.
+/// 
+/// # Params
+/// 
+/// f --- The closure to run.  
+fn with_pools<F, T,>(f: F,) -> T
+  where F: FnOnce(&mut Vec<&'static dyn Introspect>,) -> T, {
+  //The registry is only locked briefly so spin rather than park.
+  POOLS.with(f,)
+}
+
+/// Registers a resource pool.
+/// 
+/// Returns `false` if the resource pool was already registered.
+/// 
+/// # Params
+/// 
+/// pool --- The resource pool to register.  
+pub fn register(pool: &'static dyn Introspect,) -> bool {
+  let address = pool as *const dyn Introspect as *const ();
+
+  with_pools(|pools,| {
+    let registered = |&pool: &&'static dyn Introspect,| {
+      pool as *const dyn Introspect as *const () == address
+    };
+
+    if pools.iter().any(registered,) { return false }
+
+    pools.push(pool,);
+    true
+  },)
+}
+
+/// Returns every registered resource pool in order of registration.
+pub(crate) fn registered() -> Vec<&'static dyn Introspect> { with_pools(|pools,| pools.clone(),) }
+
+/// Describes every registered resource pool in order of registration.
+pub fn pools() -> Vec<PoolInfo> {
+  //Describe the resource pools without holding the registry.
+  registered().iter().map(|pool,| pool.info(),).collect()
+}
+
+/// Writes a line describing every registered resource pool.
+/// 
+/// # Params
+/// 
+/// write --- The destination to write to.  
+pub fn dump<W,>(write: &mut W,) -> fmt::Result
+  where W: fmt::Write, {
+  for pool in pools() { writeln!(write, "{}", pool,)?; }
+
+  Ok(())
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::{ResourcePool, SingleResource, MultiResource,};
+  use std::{boxed::Box, string::String, thread::{self, Thread,},};
+
+  #[test]
+  fn test_registry() {
+    static SINGLE: SingleResource<i32,> = SingleResource::INIT.with_name("single",);
+
+    let multi: &'static MultiResource<i32,> = Box::leak(
+      Box::new(MultiResource::new_resources(2,).with_name("multi",),),
+    );
+    let mut waiter = None;
+
+    //Other tests may register resource pools of their own.
+    let registered = || pools().into_iter()
+      .filter(|pool,| pool.name == "single" || pool.name == "multi",)
+      .collect::<Vec<_>>();
+
+    assert!(register(&SINGLE,),);
+    assert!(!register(&SINGLE,),);
+    assert!(register(multi,),);
+    multi.get_resource::<Thread, _,>(|_, _,| {
+      assert_eq!(
+        registered(),
+        [
+          PoolInfo { name: "single", size: 1, in_use: 0, waiters: 0, },
+          PoolInfo { name: "multi", size: 2, in_use: 1, waiters: 0, },
+        ],
+      );
+
+      SINGLE.get_resource::<Thread, _,>(|_, _,| {
+        waiter = Some(thread::spawn(|| SINGLE.get_resource::<Thread, _,>(|_, _,| {},),),);
+
+        while registered()[0].waiters == 0 { thread::yield_now(); }
+        assert_eq!(registered()[0], PoolInfo { name: "single", size: 1, in_use: 1, waiters: 1, },);
+      },);
+    },);
+    waiter.unwrap().join().unwrap();
+
+    let mut dump = String::new();
+
+    super::dump(&mut dump,).unwrap();
+
+    let dump = dump.lines()
+      .filter(|line,| line.starts_with("single:",) || line.starts_with("multi:",),)
+      .collect::<Vec<_>>();
+
+    assert_eq!(dump, ["single: size=1 in_use=0 waiters=0", "multi: size=2 in_use=0 waiters=0",],);
+  }
+}
@@ -1,8 +1,20 @@
 //! Author --- daniel.bechaz@gmail.com  
-//! Last Moddified --- 2019-06-14
+//! Last Moddified --- 2026-10-18
 
 use super::*;
-use alloc::vec::Vec;
+use crate::pollicy::Size;
+use core::{mem, ops::{Deref, DerefMut,}, sync::atomic::*,};
+use alloc::{
+  vec::Vec,
+  string::String,
+  boxed::Box,
+  collections::{VecDeque, BTreeMap, BTreeSet,},
+};
+#[cfg(feature = "std",)]
+use std::{
+  hash::BuildHasher,
+  collections::{HashMap, HashSet,},
+};
 
 macro_rules! int_resource {
   ($tp:ty,) => {
@@ -14,6 +26,13 @@ macro_rules! int_resource {
     impl ConstResource for $tp {
       const INIT: Self = 0;
     }
+
+    impl Size for $tp {}
+
+    impl Reset for $tp {
+      #[inline]
+      fn reset(&mut self,) { *self = Self::INIT }
+    }
   };
   ($tp:ty, $($rest:ty,)+) => {
     int_resource!($tp,);
@@ -37,6 +56,13 @@ macro_rules! float_resource {
     impl ConstResource for $tp {
       const INIT: Self = 0.;
     }
+
+    impl Size for $tp {}
+
+    impl Reset for $tp {
+      #[inline]
+      fn reset(&mut self,) { *self = Self::INIT }
+    }
   };
   ($tp:ty, $($rest:ty,)+) => {
     float_resource!($tp,);
@@ -50,10 +76,142 @@ macro_rules! float_resource {
   };
 }
 
+/// Implements `ConstResource` for types with a constant inital value.
+macro_rules! const_resource {
+  ($($(#[$attr:meta])* $tp:ty = $init:expr,)+) => {$(
+    $(#[$attr])*
+    impl Resource for $tp {
+      #[inline]
+      fn new() -> Self { $init }
+    }
+
+    $(#[$attr])*
+    impl ConstResource for $tp {
+      const INIT: Self = $init;
+    }
+  )+};
+}
+
+/// Implements `Resource` and `ConstResource` for tuples of resources.
+macro_rules! tuple_resource {
+  ($($name:ident,)+) => {
+    impl<$($name,)+> Resource for ($($name,)+)
+      where $($name: Resource,)+ {
+      #[inline]
+      fn new() -> Self { ($($name::new(),)+) }
+    }
+
+    impl<$($name,)+> ConstResource for ($($name,)+)
+      where $($name: ConstResource,)+ {
+      const INIT: Self = ($($name::INIT,)+);
+    }
+  };
+}
+
 int_resource!(usize, isize, u8, i8, u16, i16, u32, i32, u64, i64, u128, i128,);
 
 float_resource!(f32, f64,);
 
+const_resource!(
+  bool = false,
+  char = '\0',
+  () = (),
+  String = String::new(),
+  AtomicBool = AtomicBool::new(false,),
+  AtomicUsize = AtomicUsize::new(0,),
+  AtomicIsize = AtomicIsize::new(0,),
+  AtomicU8 = AtomicU8::new(0,),
+  AtomicI8 = AtomicI8::new(0,),
+  AtomicU16 = AtomicU16::new(0,),
+  AtomicI16 = AtomicI16::new(0,),
+  AtomicU32 = AtomicU32::new(0,),
+  AtomicI32 = AtomicI32::new(0,),
+  #[cfg(target_has_atomic = "64",)]
+  AtomicU64 = AtomicU64::new(0,),
+  #[cfg(target_has_atomic = "64",)]
+  AtomicI64 = AtomicI64::new(0,),
+);
+
+tuple_resource!(A,);
+tuple_resource!(A, B,);
+tuple_resource!(A, B, C,);
+tuple_resource!(A, B, C, D,);
+tuple_resource!(A, B, C, D, E,);
+tuple_resource!(A, B, C, D, E, F,);
+tuple_resource!(A, B, C, D, E, F, G,);
+tuple_resource!(A, B, C, D, E, F, G, H,);
+tuple_resource!(A, B, C, D, E, F, G, H, I,);
+tuple_resource!(A, B, C, D, E, F, G, H, I, J,);
+tuple_resource!(A, B, C, D, E, F, G, H, I, J, K,);
+tuple_resource!(A, B, C, D, E, F, G, H, I, J, K, L,);
+
+impl<R, const N: usize,> Resource for [R; N]
+  where R: Resource, {
+  #[inline]
+  fn new() -> Self { core::array::from_fn(|_,| R::new(),) }
+}
+
+impl<R, const N: usize,> ConstResource for [R; N]
+  where R: ConstResource, {
+  const INIT: Self = [R::INIT; N];
+}
+
+impl<R,> Resource for Option<R,> {
+  #[inline]
+  fn new() -> Self { None }
+}
+
+impl<R,> ConstResource for Option<R,> {
+  const INIT: Self = None;
+}
+
+impl<R,> Resource for Box<R,>
+  where R: Resource, {
+  #[inline]
+  fn new() -> Self { Box::new(R::new(),) }
+}
+
+impl<R,> Resource for VecDeque<R,> {
+  #[inline]
+  fn new() -> Self { VecDeque::new() }
+}
+
+impl<R,> ConstResource for VecDeque<R,> {
+  const INIT: Self = Self::new();
+}
+
+impl<K, V,> Resource for BTreeMap<K, V,> {
+  #[inline]
+  fn new() -> Self { BTreeMap::new() }
+}
+
+impl<K, V,> ConstResource for BTreeMap<K, V,> {
+  const INIT: Self = Self::new();
+}
+
+impl<R,> Resource for BTreeSet<R,> {
+  #[inline]
+  fn new() -> Self { BTreeSet::new() }
+}
+
+impl<R,> ConstResource for BTreeSet<R,> {
+  const INIT: Self = Self::new();
+}
+
+#[cfg(feature = "std",)]
+impl<K, V, S,> Resource for HashMap<K, V, S,>
+  where S: BuildHasher + Default, {
+  #[inline]
+  fn new() -> Self { HashMap::default() }
+}
+
+#[cfg(feature = "std",)]
+impl<R, S,> Resource for HashSet<R, S,>
+  where S: BuildHasher + Default, {
+  #[inline]
+  fn new() -> Self { HashSet::default() }
+}
+
 impl<R,> Resource for Vec<R,> {
   #[inline]
   fn new() -> Self { Vec::new() }
@@ -62,3 +220,177 @@ impl<R,> Resource for Vec<R,> {
 impl<R,> ConstResource for Vec<R,> {
   const INIT: Self = Self::new();
 }
+
+/// Returns the heap memory owned by the elements of a collection.
+/// 
+/// Elements without drop glue cannot own heap memory and are not visited.
+#[inline]
+fn heap_size_of<'a, R,>(elements: impl Iterator<Item = &'a R>,) -> usize
+  where R: 'a + Size, {
+  if mem::needs_drop::<R>() { elements.map(R::heap_size,).sum() } else { 0 }
+}
+
+impl<R,> Size for Vec<R,>
+  where R: Size, {
+  #[inline]
+  fn heap_size(&self,) -> usize {
+    self.capacity() * mem::size_of::<R>() + heap_size_of(self.iter(),)
+  }
+}
+
+impl<R,> Size for VecDeque<R,>
+  where R: Size, {
+  #[inline]
+  fn heap_size(&self,) -> usize {
+    self.capacity() * mem::size_of::<R>() + heap_size_of(self.iter(),)
+  }
+}
+
+impl Size for String {
+  #[inline]
+  fn heap_size(&self,) -> usize { self.capacity() }
+}
+
+impl<R,> Size for Box<R,>
+  where R: ?Sized + Size, {
+  #[inline]
+  fn heap_size(&self,) -> usize { (**self).size() }
+}
+
+/// The entries of a `HashMap` are counted but not the control bytes of its table.
+#[cfg(feature = "std",)]
+impl<K, V, S,> Size for HashMap<K, V, S,>
+  where K: Size,
+    V: Size, {
+  #[inline]
+  fn heap_size(&self,) -> usize {
+    self.capacity() * mem::size_of::<(K, V,)>()
+      + heap_size_of(self.keys(),) + heap_size_of(self.values(),)
+  }
+}
+
+impl<R,> Reset for Vec<R,> {
+  #[inline]
+  fn reset(&mut self,) { self.clear() }
+}
+
+impl Reset for String {
+  #[inline]
+  fn reset(&mut self,) { self.clear() }
+}
+
+impl<R,> Reset for VecDeque<R,> {
+  #[inline]
+  fn reset(&mut self,) { self.clear() }
+}
+
+#[cfg(feature = "std",)]
+impl<K, V, S,> Reset for HashMap<K, V, S,> {
+  #[inline]
+  fn reset(&mut self,) { self.clear() }
+}
+
+/// Adapts any `Default` type into a `Resource`.
+/// 
+/// ```rust
+/// use bottleneck::*;
+/// use std::{thread::Thread, time::Duration,};
+/// 
+/// let pool = SingleResource::<DefaultResource<Duration>>::new();
+/// 
+/// pool.get_resource::<Thread, _,>(|_, resource,| {
+///   assert_eq!(**resource, Duration::default(),);
+///   **resource += Duration::from_secs(1,);
+/// },);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug,)]
+pub struct DefaultResource<T,>(pub T,);
+
+impl<T,> Resource for DefaultResource<T,>
+  where T: Default, {
+  #[inline]
+  fn new() -> Self { DefaultResource(T::default(),) }
+}
+
+impl<T,> From<T> for DefaultResource<T,> {
+  #[inline]
+  fn from(from: T,) -> Self { DefaultResource(from,) }
+}
+
+impl<T,> Deref for DefaultResource<T,> {
+  type Target = T;
+
+  #[inline]
+  fn deref(&self,) -> &Self::Target { &self.0 }
+}
+
+impl<T,> DerefMut for DefaultResource<T,> {
+  #[inline]
+  fn deref_mut(&mut self,) -> &mut Self::Target { &mut self.0 }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use alloc::vec;
+
+  #[test]
+  fn test_resource() {
+    const INIT: (bool, char, (), [Option<u8>; 2], String,) = ConstResource::INIT;
+
+    assert_eq!(INIT, (false, '\0', (), [None, None,], String::new(),),);
+    assert_eq!(<[Box<i32>; 3]>::new(), [Box::new(0,), Box::new(0,), Box::new(0,),],);
+    assert_eq!(<AtomicUsize as Resource>::new().load(Ordering::Relaxed,), 0,);
+    assert!(<(VecDeque<u8>, BTreeMap<u8, u8>, BTreeSet<u8>,)>::INIT.0.is_empty(),);
+  }
+  #[test]
+  fn test_size() {
+    let words = Vec::<u32>::with_capacity(4,);
+    let strings = vec![String::from("ab",), String::with_capacity(8,),];
+    let boxed = Box::new(String::from("abc",),);
+
+    assert_eq!(7u64.size(), 8,);
+    assert_eq!(words.size(), mem::size_of::<Vec<u32>>() + 4 * words.capacity(),);
+    assert_eq!(
+      strings.size(),
+      mem::size_of::<Vec<String>>() + strings.capacity() * mem::size_of::<String>()
+        + strings[0].capacity() + strings[1].capacity(),
+    );
+    assert_eq!(boxed.size(), mem::size_of::<Box<String>>() + mem::size_of::<String>() + boxed.capacity(),);
+  }
+  #[test]
+  fn test_default_resource() {
+    use crate::{pollicy::NoReuse, MultiResource, ResourcePool,};
+    use core::time::Duration;
+    use std::thread::Thread;
+
+    let resource = MultiResource::<DefaultResource<Duration>, NoReuse,>::new_resources(2,);
+
+    resource.get_resource::<Thread, _,>(|_, r,| **r = Duration::from_secs(1,),);
+    resource.get_resources::<Thread, _,>(2, |_, resources,| for r in resources.iter() {
+      assert_eq!(r.as_secs(), 0,);
+    },);
+  }
+  #[cfg(feature = "derive",)]
+  #[test]
+  fn test_resource_derive() {
+    #[derive(Resource, ConstResource, PartialEq, Debug,)]
+    struct Counters<T,> {
+      hits: usize,
+      last: Option<T>,
+    }
+
+    #[derive(Resource, PartialEq, Debug,)]
+    struct Scratch(Vec<u8>, #[resource(default)] core::time::Duration,);
+
+    #[derive(Resource, Default, PartialEq, Debug,)]
+    #[resource(default)]
+    enum Mode { #[default] Idle, }
+
+    const INIT: Counters<char,> = Counters::INIT;
+
+    assert_eq!(INIT, Counters { hits: 0, last: None, },);
+    assert_eq!(Scratch::new(), Scratch(Vec::new(), core::time::Duration::from_secs(0,),),);
+    assert_eq!(Mode::new(), Mode::Idle,);
+  }
+}
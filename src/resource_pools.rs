@@ -0,0 +1,397 @@
+//! Defines the aquisition of resources from several resource pools at once.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-18
+
+use super::*;
+use core::ptr;
+
+/// A reference to a resource pool which can be aquired alongside other resource pools.
+/// 
+/// Resource pools are aquired in order of their rank, backing off whenever one is in
+/// use, so that threads aquiring the same resource pools cannot deadlock each other.
+pub trait PoolRef {
+  /// The resource pool being referenced.
+  type Pool: ResourcePool;
+
+  /// Returns the resource pool.
+  fn pool(&self,) -> &Self::Pool;
+  /// Returns the rank of the resource pool.
+  fn rank(&self,) -> usize;
+}
+
+/// A resource pool ranked by its address.
+impl<Pool,> PoolRef for &'_ Pool
+  where Pool: ResourcePool, {
+  type Pool = Pool;
+
+  #[inline]
+  fn pool(&self,) -> &Self::Pool { self }
+  #[inline]
+  fn rank(&self,) -> usize { *self as *const Pool as usize }
+}
+
+/// A resource pool with an explicit rank.
+/// 
+/// All references to a resource pool should use the same rank.
+pub struct Ranked<'pool, Pool,> {
+  /// The resource pool being referenced.
+  pub pool: &'pool Pool,
+  /// The rank of the resource pool.
+  pub rank: usize,
+}
+
+impl<Pool,> PoolRef for Ranked<'_, Pool,>
+  where Pool: ResourcePool, {
+  type Pool = Pool;
+
+  #[inline]
+  fn pool(&self,) -> &Self::Pool { self.pool }
+  #[inline]
+  fn rank(&self,) -> usize { self.rank }
+}
+
+/// A collection of resource pools which can be aquired together.
+/// 
+/// This is implemented for tuples of [PoolRef]s and runs a closure accepting the `Id`
+/// and resource from each resource pool.
+/// 
+/// `get_resources` waits on the resource pool with the lowest rank and then tries the
+/// others in order of rank. If one of them is in use every resource is released and
+/// the aquisition waits on the contended resource pool alone before trying the others
+/// again, so it never waits while holding resources from the same `ResourcePools`.
+/// Nested aquisitions still wait while holding the resources of the outer aquisition
+/// and should aquire resource pools with greater ranks.
+/// 
+/// ```rust
+/// use bottleneck::*;
+/// use std::thread::Thread;
+/// 
+/// static LOGGER: SingleResource<u32> = SingleResource::INIT;
+/// static BUFFERS: SingleResource<u64> = SingleResource::INIT;
+/// 
+/// (&LOGGER, &BUFFERS,).get_resources::<Thread,>(|(_, logger,), (_, buffer,),| {
+///   *logger += 1;
+///   *buffer += 1;
+/// },);
+/// ```
+pub trait ResourcePools<F,> {
+  /// Gets a resource from every resource pool.
+  /// 
+  /// The resource pools are aquired in order of their rank, releasing every resource and
+  /// waiting on any resource pool which is in use before trying again.
+  /// 
+  /// # Params
+  /// 
+  /// f --- The closure to run once the resources are aquired.  
+  /// 
+  /// # Panics
+  /// 
+  /// With the `lock-order` feature and debug assertions enabled, if the same resource
+  /// pool is aquired twice or this thread already holds resources aquired through a
+  /// `ResourcePools` with a greater or equal rank.
+  fn get_resources<P,>(self, f: F,)
+    where P: Park;
+  /// A non blocking equivelant of `get_resources`.
+  /// 
+  /// If any resource could not be aquired no resources are held when this returns.
+  /// 
+  /// Returns `true` if the closure was executed.
+  /// 
+  /// # Params
+  /// 
+  /// f --- The closure to run once the resources are aquired.  
+  fn try_get_resources(self, f: F,) -> bool;
+}
+
+/// A type erased aquisition of a resource which waits for the resource if the flag is set.
+/// 
+/// Returns `true` if the closure was executed.
+type Aquire<'a> = &'a dyn Fn(bool, &mut dyn FnMut(usize, *mut (),),) -> bool;
+/// A type erased closure run with the `Id` and resource aquired from each resource pool.
+type Run<'a> = &'a mut dyn FnMut(&[(usize, *mut (),)],);
+
+/// Aquires resources from resource pools in order without waiting on any resource pool
+/// but the first.
+/// 
+/// Returns `true` if the closure was executed, otherwise `contended` is set to the index
+/// of the resource pool which could not be aquired.
+/// 
+/// # Params
+/// 
+/// order --- The indexes of the resource pools to aquire, in order.  
+/// pools --- The resource pools.  
+/// held --- The `Id` and resource aquired from each resource pool.  
+/// f --- The closure to run once all of the resources are aquired.  
+/// wait --- Wait for the first resource pool.  
+/// contended --- The index of the resource pool which could not be aquired.  
+fn aquire_ordered(
+  order: &[usize],
+  pools: &[Aquire],
+  held: &mut [(usize, *mut (),)],
+  f: Run,
+  wait: bool,
+  contended: &mut usize,
+) -> bool {
+  let (&index, rest,) = match order.split_first() {
+    Some(next) => next,
+    None => { f(held,); return true },
+  };
+  let mut aquired = false;
+  let ran = pools[index](wait, &mut |id, resource,| {
+    held[index] = (id, resource,);
+    aquired = aquire_ordered(rest, pools, held, f, false, contended,);
+  },);
+
+  if !ran { *contended = index }
+
+  ran && aquired
+}
+
+/// Aquires resources from resource pools in order of rank.
+/// 
+/// When waiting, a resource pool which is in use causes every resource to be released
+/// and is waited on first in the next attempt, followed by the others in order of rank.
+/// 
+/// Returns `true` if the closure was executed.
+/// 
+/// # Params
+/// 
+/// ranks --- The rank of each resource pool.  
+/// order --- Storage for the order of the resource pools.  
+/// pools --- The resource pools.  
+/// held --- The `Id` and resource aquired from each resource pool.  
+/// f --- The closure to run once all of the resources are aquired.  
+/// wait --- Wait for the resources instead of failing.  
+fn aquire_ranked(
+  ranks: &[usize],
+  order: &mut [usize],
+  pools: &[Aquire],
+  held: &mut [(usize, *mut (),)],
+  f: Run,
+  wait: bool,
+) -> bool {
+  order.sort_by_key(|&index,| ranks[index],);
+
+  #[cfg(all(debug_assertions, feature = "lock-order",),)]
+  let _order = lock_order::Order::lock(ranks, order,);
+
+  //The first attempt waits on the resource pool with the lowest rank.
+  let mut contended = order[0];
+
+  loop {
+    order.sort_by_key(|&index,| ranks[index],);
+    //Move the contended resource pool to the front, keeping the others in order of rank.
+    if let Some(first) = order.iter().position(|&index,| index == contended,) {
+      order[..=first].rotate_right(1,);
+    }
+
+    if aquire_ordered(order, pools, held, f, wait, &mut contended,) { return true }
+    if !wait { return false }
+  }
+}
+
+/// Checks that resource pools are aquired in a consistent order.
+#[cfg(all(debug_assertions, feature = "lock-order",),)]
+mod lock_order {
+  use std::{cell::RefCell, vec::Vec,};
+
+  std::thread_local! {
+    /// The ranks of the resource pools held by this thread, in order of aquisition.
+    static HELD: RefCell<Vec<usize>> = const { RefCell::new(Vec::new(),) };
+  }
+
+  /// Records the ranks held by this thread until dropped.
+  pub struct Order(usize,);
+
+  impl Order {
+    /// Records that this thread is aquiring resource pools.
+    /// 
+    /// # Params
+    /// 
+    /// ranks --- The rank of each resource pool.  
+    /// order --- The indexes of the resource pools to aquire, in order.  
+    /// 
+    /// # Panics
+    /// 
+    /// If the resource pools are not aquired in order of rank.
+    pub fn lock(ranks: &[usize], order: &[usize],) -> Self {
+      HELD.with(|held,| {
+        let mut held = held.borrow_mut();
+
+        for &index in order {
+          let rank = ranks[index];
+
+          if let Some(&last) = held.last() {
+            assert!(
+              last < rank,
+              "resource pool with rank `{}` aquired while holding rank `{}`", rank, last,
+            );
+          }
+
+          held.push(rank,);
+        }
+      },);
+
+      Order(order.len(),)
+    }
+  }
+
+  impl Drop for Order {
+    fn drop(&mut self,) {
+      //A panic may leave fewer ranks than were pushed.
+      let _ = HELD.try_with(|held,| {
+        let mut held = held.borrow_mut();
+        let len = held.len().saturating_sub(self.0,);
+
+        held.truncate(len,);
+      },);
+    }
+  }
+}
+
+macro_rules! resource_pools {
+  ($count:expr; $($pool:ident $index:tt,)+) => {
+    impl<$($pool,)+ F,> ResourcePools<F,> for ($($pool,)+)
+      where $($pool: PoolRef,)+
+        F: FnOnce($((usize, &mut <$pool::Pool as ResourcePool>::Resource,),)+), {
+      fn get_resources<P,>(self, f: F,)
+        where P: Park, {
+        let pools: [Aquire; $count] = [$(&|wait, f,| {
+          let f = |id, resource: &mut _,| f(id, resource as *mut _ as *mut (),);
+
+          if !wait { return self.$index.pool().try_get_resource(f,) }
+
+          self.$index.pool().get_resource::<P, _,>(f,);
+          true
+        },)+];
+
+        aquire_pools!(self, pools, f, true; $count; $($pool $index,)+);
+      }
+      fn try_get_resources(self, f: F,) -> bool {
+        let pools: [Aquire; $count] = [$(&|_, f,| {
+          self.$index.pool().try_get_resource(
+            |id, resource,| f(id, resource as *mut _ as *mut (),),
+          )
+        },)+];
+
+        aquire_pools!(self, pools, f, false; $count; $($pool $index,)+)
+      }
+    }
+  };
+}
+
+macro_rules! aquire_pools {
+  ($self:ident, $pools:ident, $f:ident, $wait:expr; $count:expr; $($pool:ident $index:tt,)+) => {{
+    let ranks = [$($self.$index.rank(),)+];
+    let mut order = [$($index,)+];
+    let mut held = [(0, ptr::null_mut(),); $count];
+    let mut f = Some($f,);
+
+    aquire_ranked(&ranks, &mut order, &$pools, &mut held, &mut |held,| {
+      if let Some(f) = f.take() {
+        //Each resource is only referenced once and only for the duration of the closure.
+        f($((
+          held[$index].0,
+          unsafe { &mut *(held[$index].1 as *mut <$pool::Pool as ResourcePool>::Resource) },
+        ),)+)
+      }
+    }, $wait,)
+  }};
+}
+
+resource_pools!(2; A 0, B 1,);
+resource_pools!(3; A 0, B 1, C 2,);
+resource_pools!(4; A 0, B 1, C 2, D 3,);
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use crate::pollicy::*;
+  use std::{vec, vec::Vec, thread::{self, Thread,}, sync::Arc,};
+
+  #[test]
+  fn test_resource_pools() {
+    let single = SingleResource::<i32, Reuse,>::new();
+    let multi = MultiResource::<usize, Reuse,>::with_resources(vec![0; 2],);
+
+    (&single, &multi,).get_resources::<Thread,>(|(_, single,), (id, multi,),| {
+      *single = 1; *multi = id;
+    },);
+    (&multi, &single,).get_resources::<Thread,>(|(id, multi,), (_, single,),| {
+      assert_eq!(*single, 1,);
+      assert_eq!(*multi, id,);
+    },);
+    single.get_resource::<Thread, _,>(|_, _,| {
+      assert!(!(&single, &multi,).try_get_resources(|_, _,| {},),);
+      //No resource from `multi` is held after the failed attempt.
+      assert!(multi.try_get_resources(2, |_, _,| {},),);
+    },);
+  }
+  #[test]
+  fn test_resource_pools_multithread() {
+    const THREADS: usize = 8;
+    const CYCLES: usize = 200;
+
+    let first = Arc::new(SingleResource::<usize, Reuse,>::new(),);
+    let second = Arc::new(MultiResource::<usize, Reuse,>::new_resources(2,),);
+
+    let handles = (0..THREADS).map(|thread,| {
+      let (first, second,) = (first.clone(), second.clone(),);
+
+      thread::spawn(move || for _ in 0..CYCLES {
+        let first = Ranked { pool: &*first, rank: 0, };
+        let second = Ranked { pool: &*second, rank: 1, };
+
+        //Alternate the order the pools are listed in.
+        if thread % 2 == 0 {
+          (first, second,).get_resources::<Thread,>(|(_, first,), _,| *first += 1,);
+        } else {
+          (second, first,).get_resources::<Thread,>(|_, (_, first,),| *first += 1,);
+        }
+      },)
+    },).collect::<Vec<_>>();
+
+    for handle in handles { handle.join().unwrap(); }
+
+    first.get_resource::<Thread, _,>(|_, first,| assert_eq!(*first, THREADS * CYCLES,),);
+  }
+  #[test]
+  fn test_resource_pools_back_off() {
+    const THREADS: usize = 8;
+    const CYCLES: usize = 200;
+
+    let first = SingleResource::<usize, Reuse,>::new();
+    let second = SingleResource::<usize, Reuse,>::new();
+
+    thread::scope(|scope,| for thread in 0..THREADS {
+      let (first, second,) = (&first, &second,);
+
+      scope.spawn(move || for _ in 0..CYCLES {
+        //Half of the threads rank the resource pools in the opposite order.
+        let rank = thread % 2;
+        let pools = (Ranked { pool: first, rank, }, Ranked { pool: second, rank: 1 - rank, },);
+
+        pools.get_resources::<Thread,>(|(_, first,), (_, second,),| { *first += 1; *second += 1; },);
+      },);
+    },);
+
+    first.get_resource::<Thread, _,>(|_, first,| assert_eq!(*first, THREADS * CYCLES,),);
+    second.get_resource::<Thread, _,>(|_, second,| assert_eq!(*second, THREADS * CYCLES,),);
+  }
+  #[cfg(feature = "lock-order",)]
+  #[test]
+  #[should_panic(expected = "aquired while holding rank",)]
+  fn test_resource_pools_lock_order() {
+    let first = SingleResource::<i32,>::new();
+    let second = SingleResource::<i32,>::new();
+    let third = SingleResource::<i32,>::new();
+    let outer = (Ranked { pool: &second, rank: 1, }, Ranked { pool: &third, rank: 2, },);
+
+    outer.get_resources::<Thread,>(|_, _,| {
+      let inner = (Ranked { pool: &first, rank: 0, }, Ranked { pool: &third, rank: 3, },);
+
+      inner.get_resources::<Thread,>(|_, _,| {},);
+    },);
+  }
+}
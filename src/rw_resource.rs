@@ -0,0 +1,358 @@
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-18
+
+use super::*;
+use crate::{pollicy::*, trace, debug_locks::{self, PoolId, Wait,}, wait_queue::WaitQueue,};
+use core::{cell::UnsafeCell, sync::atomic::{AtomicUsize, Ordering,},};
+
+/// The flag set in the state of a `RwResource` while the resource is being written.
+const WRITER: usize = !(usize::MAX >> 1);
+
+/// Stores a single resource which many threads can read at once or one thread can write.
+/// 
+/// Threads waiting to write the resource are preferred over new readers so that writers
+/// are not starved.
+/// 
+/// The reuse pollicy is only applied after the resource has been written.
+pub struct RwResource<R, Pollicy = Reuse,> {
+  /// The resource instance to use.
+  resource: UnsafeCell<R>,
+  /// The number of threads reading the resource or `WRITER` if it is being written.
+  state: AtomicUsize,
+  /// A queue of threads waiting to read this resource.
+  read_queue: WaitQueue,
+  /// A queue of threads waiting to write this resource.
+  write_queue: WaitQueue,
+  /// The number of times the resource instance has been written.
+  uses: AtomicUsize,
+  /// When the resource instance was created.
+  created: Created,
+  /// The reuse pollicy of this resource pool.
+  pollicy: Pollicy,
+}
+
+unsafe impl<R, Pol,> Sync for RwResource<R, Pol,>
+  where R: Send + Sync,
+    Pol: Sync, {}
+
+impl<R, P,> RwResource<R, P,>
+  where R: ConstResource,
+    P: ConstPollicy, {
+  /// A constant inital resource pool.
+  #[allow(clippy::declare_interior_mutable_const,)]
+  pub const INIT: Self = Self::with_resource(R::INIT,);
+}
+
+impl<R, P,> RwResource<R, P,>
+  where R: Resource,
+    P: ConstPollicy, {
+  /// Creates a new resource pool.
+  pub fn new() -> Self { Self::with_resource(R::new(),) }
+}
+
+impl<R, P,> Default for RwResource<R, P,>
+  where R: Resource,
+    P: ConstPollicy, {
+  #[inline]
+  fn default() -> Self { Self::new() }
+}
+
+impl<R, Pol,> RwResource<R, Pol,> {
+  /// Creates a new resource pool.
+  /// 
+  /// # Param
+  /// 
+  /// resource --- The `Resource` to use.  
+  pub const fn with_resource(resource: R,) -> Self
+    where Pol: ConstPollicy, { Self::with_pollicy(resource, Pol::INIT,) }
+  /// Creates a new resource pool with a reuse pollicy.
+  /// 
+  /// # Param
+  /// 
+  /// resource --- The `Resource` to use.  
+  /// pollicy --- The reuse pollicy to use.  
+  pub const fn with_pollicy(resource: R, pollicy: Pol,) -> Self {
+    Self {
+      resource: UnsafeCell::new(resource,),
+      state: AtomicUsize::new(0,),
+      read_queue: WaitQueue::new(),
+      write_queue: WaitQueue::new(),
+      uses: AtomicUsize::new(0,),
+      created: Created::new(),
+      pollicy,
+    }
+  }
+  /// Consumes the resource pool and returns the resource.
+  #[inline]
+  pub fn into_inner(self,) -> R { self.resource.into_inner() }
+  /// Gets the resource mutably.
+  /// 
+  /// The exclusive borrow guarantees the resource is not in use.
+  #[inline]
+  pub fn get_mut(&mut self,) -> &mut R { self.resource.get_mut() }
+  /// Identifies this resource pool.
+  #[inline]
+  fn pool_id(&self,) -> PoolId { PoolId::of(self, 1,) }
+  /// Attempts to lock the resource for reading.
+  /// 
+  /// Returns `true` if the resource was locked.
+  fn lock_read(&self,) -> bool {
+    //Give way to waiting writers.
+    if self.write_queue.len() != 0 { return false }
+
+    let mut state = self.state.load(Ordering::Relaxed,);
+
+    loop {
+      if state & WRITER != 0 { return false }
+
+      match self.state.compare_exchange_weak(
+        state, state + 1, Ordering::Acquire, Ordering::Relaxed,
+      ) {
+        Ok(_) => return true,
+        Err(current) => state = current,
+      }
+    }
+  }
+  /// Unlocks the resource after reading.
+  fn unlock_read(&self,) {
+    debug_locks::released(self.pool_id(), 0,);
+    //The last reader wakes the waiting threads.
+    if self.state.fetch_sub(1, Ordering::Release,) == 1 { self.wake() }
+  }
+  /// Attempts to lock the resource for writing.
+  /// 
+  /// Returns `true` if the resource was locked.
+  fn lock_write(&self,) -> bool {
+    self.state.compare_exchange(0, WRITER, Ordering::Acquire, Ordering::Relaxed,).is_ok()
+  }
+  /// Unlocks the resource after writing.
+  fn unlock_write(&self,) {
+    debug_locks::released(self.pool_id(), 0,);
+    //Release the lock.
+    self.state.store(0, Ordering::Release,);
+    self.wake();
+  }
+  /// Wakes a waiting writer or, if there are none, all waiting readers.
+  fn wake(&self,) {
+    if !self.write_queue.pop() { self.read_queue.pop_all() }
+  }
+  /// Attempts to claim the resource for reading.
+  /// 
+  /// Returns `None` if the resource could not be locked.
+  fn attempt_read(&self,) -> Option<FinishRead<'_, R, Pol,>> {
+    //Attempt to lock the resource.
+    if self.lock_read() { Some(FinishRead { pool: self, },) } else { None }
+  }
+  /// Runs the closure reading a claimed resource.
+  /// 
+  /// # Params
+  /// 
+  /// finish --- Unlocks the resource.  
+  /// f --- The closure to run.  
+  fn run_read<F,>(&self, finish: FinishRead<'_, R, Pol,>, f: F,)
+    where F: FnOnce(usize, &R,), {
+    let _finish = finish;
+
+    debug_locks::aquired(self.pool_id(), 0,);
+
+    let _holding = trace::holding(self.pool_id(), 0,);
+
+    //Aquired the resource.
+    f(0, unsafe { &*self.resource.get() },);
+  }
+}
+
+/// Unlocks the resource after reading when dropped.
+struct FinishRead<'pool, R, Pol,> {
+  /// The resource pool being read.
+  pool: &'pool RwResource<R, Pol,>,
+}
+
+impl<R, Pol,> Drop for FinishRead<'_, R, Pol,> {
+  fn drop(&mut self,) { self.pool.unlock_read() }
+}
+
+/// Checks the reuse pollicy and unlocks the resource after writing when dropped.
+struct Finish<'pool, R, Pol,>
+  where R: Resource,
+    Pol: PolicyKind<R,>, {
+  /// The resource pool being written.
+  pool: &'pool RwResource<R, Pol,>,
+  /// Times how long the resource is held.
+  timer: Timer,
+}
+
+impl<R, Pol,> Drop for Finish<'_, R, Pol,>
+  where R: Resource,
+    Pol: PolicyKind<R,>, {
+  fn drop(&mut self,) {
+    let usage = Usage {
+      id: 0,
+      uses: self.pool.uses.fetch_add(1, Ordering::Relaxed,) + 1,
+      held: self.timer.elapsed(),
+      age: unsafe { self.pool.created.age(self.timer,) },
+    };
+
+    //Check if the resource should be reused.
+    let resource = unsafe { &mut *self.pool.resource.get() };
+    if !self.pool.pollicy.reuse(resource, &usage,) {
+      *resource = R::new();
+      self.pool.uses.store(0, Ordering::Relaxed,);
+      unsafe { self.pool.created.reset(self.pool.pollicy.timed(),); }
+      trace::recreated(self.pool.pool_id(), 0,);
+    }
+
+    //Release the lock.
+    self.pool.unlock_write();
+  }
+}
+
+impl<R, Pol,> RwResource<R, Pol,>
+  where R: Resource,
+    Pol: PolicyKind<R,>, {
+  /// Attempts to claim the resource for writing.
+  /// 
+  /// Returns `None` if the resource could not be locked.
+  fn attempt_aquire(&self,) -> Option<Finish<'_, R, Pol,>> {
+    //Attempt to lock the resource.
+    if !self.lock_write() { return None }
+
+    Some(Finish { pool: self, timer: Timer::start_if(self.pollicy.timed(),), },)
+  }
+  /// Runs the closure writing a claimed resource.
+  /// 
+  /// # Params
+  /// 
+  /// finish --- Unlocks the resource.  
+  /// f --- The closure to run.  
+  fn run<F,>(&self, finish: Finish<'_, R, Pol,>, f: F,)
+    where F: FnOnce(usize, &mut R,), {
+    let _finish = finish;
+
+    debug_locks::aquired(self.pool_id(), 0,);
+
+    let _holding = trace::holding(self.pool_id(), 0,);
+
+    //Aquired the resource.
+    f(0, unsafe { &mut *self.resource.get() },);
+  }
+}
+
+unsafe impl<R, Pol,> ResourcePool for RwResource<R, Pol,>
+  where R: Resource,
+    Pol: PolicyKind<R,>, {
+  type Resource = R;
+
+  #[inline]
+  fn try_get_resource<F,>(&self, f: F,) -> bool
+    where F: FnOnce(usize, &mut Self::Resource,), {
+    match self.attempt_aquire() {
+      Some(finish) => { self.run(finish, f,); true },
+      None => false,
+    }
+  }
+  fn get_resource<P, F,>(&self, f: F,)
+    where P: Park,
+      F: FnOnce(usize, &mut Self::Resource,), {
+    //Attempt to write the resource, waiting for it to be released between attempts.
+    //Waiting writers stop new readers locking the resource.
+    let finish = self.write_queue.wait::<P, _, _, _,>(
+      || self.attempt_aquire(),
+      || self.wake(),
+      self.pool_id(), Wait::Any(1,),
+    );
+
+    self.run(finish, f,);
+  }
+}
+
+unsafe impl<R, Pol,> RwResourcePool for RwResource<R, Pol,>
+  where R: Resource,
+    Pol: PolicyKind<R,>, {
+  #[inline]
+  fn try_read_resource<F,>(&self, f: F,) -> bool
+    where F: FnOnce(usize, &Self::Resource,), {
+    match self.attempt_read() {
+      Some(finish) => { self.run_read(finish, f,); true },
+      None => false,
+    }
+  }
+  fn read_resource<P, F,>(&self, f: F,)
+    where P: Park,
+      F: FnOnce(usize, &Self::Resource,), {
+    //Attempt to read the resource, waiting for writers to finish between attempts.
+    let finish = self.read_queue.wait::<P, _, _, _,>(
+      || self.attempt_read(),
+      || {},
+      self.pool_id(), Wait::Any(1,),
+    );
+
+    self.run_read(finish, f,);
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use std::{thread::{self, Thread,}, sync::{Arc, Barrier,}, vec::Vec,};
+
+  #[test]
+  fn test_rw_resource() {
+    let resource = RwResource::<i32, Reuse,>::new();
+
+    resource.write_resource::<Thread, _,>(|_, r,| *r = 1,);
+    resource.read_resource::<Thread, _,>(|_, r,| {
+      assert_eq!(*r, 1,);
+      assert!(resource.try_read_resource(|_, r,| assert_eq!(*r, 1,),),);
+      assert!(!resource.try_write_resource(|_, _,| {},),);
+    },);
+    resource.write_resource::<Thread, _,>(|_, _,| {
+      assert!(!resource.try_read_resource(|_, _,| {},),);
+    },);
+
+    let resource = RwResource::<i32, NoReuse,>::new();
+
+    resource.write_resource::<Thread, _,>(|_, r,| *r = 1,);
+    resource.read_resource::<Thread, _,>(|_, r,| assert_eq!(*r, 0,),);
+
+    let mut resource = RwResource::<i32, Reuse,>::new();
+
+    *resource.get_mut() = 2;
+    resource.read_resource::<Thread, _,>(|_, r,| assert_eq!(*r, 2,),);
+    assert_eq!(resource.into_inner(), 2,);
+  }
+  #[test]
+  fn test_rw_resource_multithread() {
+    const READERS: usize = 4;
+    const WRITERS: usize = 4;
+    const CYCLES: usize = 200;
+
+    let resource = Arc::new(RwResource::<usize, Reuse,>::new(),);
+    let barrier = Arc::new(Barrier::new(READERS,),);
+
+    //All readers hold the resource at once.
+    let handles = (0..READERS).map(|_,| {
+      let (resource, barrier,) = (resource.clone(), barrier.clone(),);
+
+      thread::spawn(move || resource.read_resource::<Thread, _,>(|_, _,| { barrier.wait(); },),)
+    },).collect::<Vec<_>>();
+
+    for handle in handles { handle.join().unwrap(); }
+
+    let handles = (0..READERS + WRITERS).map(|thread,| {
+      let resource = resource.clone();
+
+      thread::spawn(move || for _ in 0..CYCLES {
+        if thread < WRITERS {
+          resource.write_resource::<Thread, _,>(|_, r,| *r += 1,);
+        } else {
+          resource.read_resource::<Thread, _,>(|_, r,| assert!(*r <= WRITERS * CYCLES,),);
+        }
+      },)
+    },).collect::<Vec<_>>();
+
+    for handle in handles { handle.join().unwrap(); }
+
+    resource.read_resource::<Thread, _,>(|_, r,| assert_eq!(*r, WRITERS * CYCLES,),);
+  }
+}
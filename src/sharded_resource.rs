@@ -0,0 +1,306 @@
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-18
+
+use super::*;
+use crate::{pollicy::*, trace, debug_locks::{self, PoolId, Wait,}, wait_queue::WaitQueue,};
+use core::{hash::{Hash, Hasher,}, sync::atomic::{self, Ordering,},};
+use std::{thread, collections::hash_map::DefaultHasher, vec::Vec,};
+
+std::thread_local! {
+  /// A hash of the current thread's Id used to choose its home shard.
+  static THREAD_HASH: usize = {
+    let mut hasher = DefaultHasher::new();
+
+    thread::current().id().hash(&mut hasher,);
+    hasher.finish() as usize
+  };
+}
+
+/// Stores multiple resource instances split across several shards.
+/// 
+/// Each thread aquires resources from the shard chosen by a hash of its thread Id and
+/// only steals from the other shards when its own shard is empty, so threads contend
+/// on different lists of available resources.
+/// 
+/// Resource Ids are unique across every shard.
+pub struct ShardedResource<R, Pollicy = Reuse,> {
+  /// The shards of resources.
+  shards: Vec<MultiResource<R, Pollicy,>>,
+  /// The Id of the first resource in each shard.
+  offsets: Vec<usize>,
+  /// The number of resources in all shards.
+  len: usize,
+  /// A queue of threads waiting to access a resource from any shard.
+  queue: WaitQueue,
+}
+
+impl<R, P,> ShardedResource<R, P,>
+  where R: Resource,
+    P: ConstPollicy, {
+  /// Creates a new resource pool of `count` new elements split across `shards` shards.
+  /// 
+  /// # Params
+  /// 
+  /// count --- The number of resources to create.  
+  /// shards --- The number of shards to split the resources across.  
+  /// 
+  /// # Panics
+  /// 
+  /// If `shards` is `0`.
+  pub fn new_resources(count: usize, shards: usize,) -> Self {
+    Self::with_resources((0..count).map(|_,| R::new(),).collect(), shards,)
+  }
+}
+
+impl<R, Pol,> ShardedResource<R, Pol,> {
+  /// Creates a new resource pool.
+  /// 
+  /// # Param
+  /// 
+  /// resources --- The `Resource`s to use.  
+  /// shards --- The number of shards to split the resources across.  
+  /// 
+  /// # Panics
+  /// 
+  /// If `shards` is `0`.
+  pub fn with_resources(resources: Vec<R>, shards: usize,) -> Self
+    where Pol: ConstPollicy, {
+    Self::from_shards(resources, shards, |resources,| MultiResource::with_resources(resources,),)
+  }
+  /// Creates a new resource pool with a reuse pollicy.
+  /// 
+  /// Each shard uses a clone of the pollicy.
+  /// 
+  /// # Param
+  /// 
+  /// resources --- The `Resource`s to use.  
+  /// shards --- The number of shards to split the resources across.  
+  /// pollicy --- The reuse pollicy to use.  
+  /// 
+  /// # Panics
+  /// 
+  /// If `shards` is `0`.
+  pub fn with_pollicy(resources: Vec<R>, shards: usize, pollicy: Pol,) -> Self
+    where Pol: Clone, {
+    Self::from_shards(
+      resources, shards,
+      |resources,| MultiResource::with_pollicy(resources, pollicy.clone(),),
+    )
+  }
+  /// Splits the resources into shards.
+  /// 
+  /// # Param
+  /// 
+  /// resources --- The `Resource`s to use.  
+  /// shards --- The number of shards to split the resources across.  
+  /// shard --- Creates a shard from its resources.  
+  fn from_shards<F,>(mut resources: Vec<R>, shards: usize, mut shard: F,) -> Self
+    where F: FnMut(Vec<R>,) -> MultiResource<R, Pol,>, {
+    assert!(shards != 0, "a `ShardedResource` needs at least one shard",);
+
+    let len = resources.len();
+    let mut offsets = Vec::with_capacity(shards,);
+    let mut pools = Vec::with_capacity(shards,);
+    let mut offset = 0;
+
+    for index in 0..shards {
+      //Spread any remainder over the first shards.
+      let size = len / shards + if index < len % shards { 1 } else { 0 };
+      let rest = resources.split_off(size,);
+
+      offsets.push(offset,);
+      pools.push(shard(core::mem::replace(&mut resources, rest,),),);
+      offset += size;
+    }
+
+    Self { shards: pools, offsets, len, queue: WaitQueue::new(), }
+  }
+  /// Returns the number of shards in this resource pool.
+  #[inline]
+  pub fn shards(&self,) -> usize { self.shards.len() }
+  /// Returns the index of the current thread's home shard.
+  #[inline]
+  fn home(&self,) -> usize { THREAD_HASH.with(|hash,| *hash,) % self.shards.len() }
+  /// Identifies this resource pool.
+  #[inline]
+  fn pool_id(&self,) -> PoolId { PoolId::of(self, self.len,) }
+}
+
+/// Checks the reuse pollicy and releases a claimed resource when dropped.
+struct Finish<'pool, R, Pol,>
+  where R: Resource,
+    Pol: PolicyKind<R,>, {
+  /// The index of the shard the resource belongs to.
+  shard: usize,
+  /// The Id of the resource within its shard.
+  resource: usize,
+  /// Times how long the resource is held.
+  timer: Timer,
+  /// The resource pool the resource belongs to.
+  pool: &'pool ShardedResource<R, Pol,>,
+}
+
+impl<R, Pol,> Drop for Finish<'_, R, Pol,>
+  where R: Resource,
+    Pol: PolicyKind<R,>, {
+  fn drop(&mut self,) {
+    let pool = self.pool;
+    let shard = &pool.shards[self.shard];
+    let id = pool.offsets[self.shard] + self.resource;
+
+    shard.recycle(self.resource, id, self.timer,);
+    debug_locks::released(pool.pool_id(), id,);
+    shard.release_resource(self.resource,);
+    //Pairs with the fence in `ShardedResource::attempt_aquire`.
+    atomic::fence(Ordering::SeqCst,);
+    //Wake a thread waiting on any shard.
+    if pool.queue.len() != 0 { pool.queue.pop(); }
+  }
+}
+
+impl<R, Pol,> ShardedResource<R, Pol,>
+  where R: Resource,
+    Pol: PolicyKind<R,>, {
+  /// Runs the closure using a claimed resource.
+  /// 
+  /// # Params
+  /// 
+  /// finish --- Releases the claimed resource.  
+  /// f --- The closure to run.  
+  fn run<F,>(&self, finish: Finish<'_, R, Pol,>, f: F,)
+    where F: FnOnce(usize, &mut R,), {
+    let (shard, resource,) = (finish.shard, finish.resource,);
+    let id = self.offsets[shard] + resource;
+
+    debug_locks::aquired(self.pool_id(), id,);
+
+    let instance = unsafe { &mut *self.shards[shard].resource(resource,) };
+    let _holding = trace::holding(self.pool_id(), id,);
+
+    self.shards[shard].hooks.on_acquire(id, instance,);
+    f(id, instance,);
+  }
+  /// Attempts to claim a resource from the home shard, or steal one from another shard.
+  /// 
+  /// Returns `None` if no resource could be aquired.
+  fn attempt_aquire(&self,) -> Option<Finish<'_, R, Pol,>> {
+    let home = self.home();
+    let shards = self.shards.len();
+
+    //Pairs with the fence in `Finish::drop`.
+    atomic::fence(Ordering::SeqCst,);
+    //Check the home shard before the other shards.
+    (0..shards).map(|index,| (home + index) % shards,).find_map(|shard,| {
+      let resource = self.shards[shard].take_resource(Vec::pop,)?;
+
+      self.shards[shard].stats.aquired();
+      Some(Finish { shard, resource, timer: Timer::start_if(self.shards[shard].timed(),), pool: self, },)
+    },)
+  }
+}
+
+unsafe impl<R, Pol,> ResourcePool for ShardedResource<R, Pol,>
+  where R: Resource,
+    Pol: PolicyKind<R,>, {
+  type Resource = R;
+
+  #[inline]
+  fn try_get_resource<F,>(&self, f: F,) -> bool
+    where F: FnOnce(usize, &mut Self::Resource,), {
+    //Attempt to aquire the resource.
+    match self.attempt_aquire() {
+      Some(finish) => { self.run(finish, f,); true },
+      None => false,
+    }
+  }
+  fn get_resource<P, F,>(&self, f: F,)
+    where P: Park,
+      F: FnOnce(usize, &mut Self::Resource,), {
+    //Attempt to aquire a resource, waiting for a resource to be released between attempts.
+    let finish = self.queue.wait::<P, _, _, _,>(
+      || self.attempt_aquire(),
+      || { self.queue.pop(); },
+      self.pool_id(), Wait::Any(1,),
+    );
+
+    self.run(finish, f,);
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use std::{vec, thread::Thread, sync::Barrier,};
+
+  #[test]
+  fn test_sharded_resource() {
+    let resource = ShardedResource::<usize, Reuse,>::with_resources(vec![0; 5], 3,);
+    let mut ids = Vec::new();
+
+    assert_eq!(resource.shards(), 3,);
+    assert_eq!(resource.offsets, [0, 2, 4,],);
+
+    //Every resource is reachable from one thread by stealing.
+    fn take_all(resource: &ShardedResource<usize, Reuse,>, ids: &mut Vec<usize>,) {
+      if !resource.try_get_resource(|id, r,| { *r += 1; ids.push(id,); take_all(resource, ids,); },) {
+        assert_eq!(ids.len(), 5,);
+      }
+    }
+    take_all(&resource, &mut ids,);
+
+    ids.sort();
+    assert_eq!(ids, [0, 1, 2, 3, 4,],);
+  }
+  #[test]
+  fn test_sharded_resource_multithread() {
+    const THREADS: usize = 16;
+    const CYCLES: usize = 500;
+
+    let resource = ShardedResource::<usize, Reuse,>::new_resources(4, 4,);
+    let barrier = Barrier::new(THREADS,);
+
+    thread::scope(|scope,| for _ in 0..THREADS {
+      scope.spawn(|| {
+        barrier.wait();
+        for _ in 0..CYCLES { resource.get_resource::<Thread, _,>(|_, r,| *r += 1,); }
+      },);
+    },);
+
+    let mut total = 0;
+
+    for shard in resource.shards.iter() {
+      shard.get_resource::<Thread, _,>(|_, r,| total += *r,);
+    }
+    assert_eq!(total, THREADS * CYCLES,);
+  }
+  #[test]
+  fn test_sharded_resource_pollicy() {
+    use std::sync::{Arc, Mutex,};
+
+    /// Records the Id of every released resource.
+    #[derive(Clone, Default,)]
+    struct Ids(Arc<Mutex<Vec<usize>>>,);
+
+    impl ReusePollicy<usize,> for Ids {
+      fn reuse(&self, resource: &mut usize, usage: &Usage,) -> bool {
+        //Each resource holds its own Id.
+        assert_eq!(*resource, usage.id,);
+        self.0.lock().unwrap().push(usage.id,);
+        true
+      }
+    }
+
+    let ids = Ids::default();
+    let resource = ShardedResource::with_pollicy((0..5).collect(), 3, Pollicy(ids.clone(),),);
+
+    fn take_all(resource: &ShardedResource<usize, Pollicy<Ids,>,>,) {
+      resource.try_get_resource(|id, r,| { assert_eq!(*r, id,); take_all(resource,); },);
+    }
+    take_all(&resource,);
+
+    let mut ids = ids.0.lock().unwrap().clone();
+
+    ids.sort();
+    assert_eq!(ids, [0, 1, 2, 3, 4,],);
+  }
+}
@@ -1,261 +1,382 @@
 //! Author --- daniel.bechaz@gmail.com  
-//! Last Moddified --- 2019-06-15
+//! Last Moddified --- 2026-10-18
 
 use super::*;
-use crate::pollicy::*;
-use sync_stack::*;
+use crate::{pollicy::*, trace, hooks::Hooks, registry::{Introspect, PoolInfo,}, stats::PoolStats, debug_locks::{self, PoolId, Wait,}, wait_queue::{WaitQueue, Waiting,},};
 use core::{
-  sync::atomic::{AtomicBool, Ordering,},
-  marker::PhantomData,
+  mem,
+  cell::UnsafeCell,
+  sync::atomic::{AtomicBool, AtomicUsize, Ordering,},
 };
 
 /// Stores a single resource and forces all threads to access it one at a time.
 pub struct SingleResource<R, Pollicy = Reuse,> {
   /// The resource instance to use.
-  resource: R,
+  resource: UnsafeCell<R>,
   /// A flag indicating if the resource is currently in use.
   in_use: AtomicBool,
-  /// A stack of threads waiting to access this resource.
-  sync_stack: SyncStack,
-  _data: PhantomData<Pollicy>,
+  /// A flag indicating if the resource pool has been closed.
+  closed: AtomicBool,
+  /// A queue of threads waiting to access this resource.
+  queue: WaitQueue,
+  /// The number of times the resource instance has been used.
+  uses: AtomicUsize,
+  /// When the resource instance was created.
+  created: Created,
+  /// The reuse pollicy of this resource pool.
+  pollicy: Pollicy,
+  /// The callbacks run as the resource is used.
+  hooks: Hooks<R,>,
+  /// The name of the resource pool.
+  name: Option<&'static str>,
+  /// Statistics about how the resource has been used.
+  stats: PoolStats,
 }
 
+unsafe impl<R, Pol,> Sync for SingleResource<R, Pol,>
+  where R: Send,
+    Pol: Sync, {}
+
 impl<R, P,> SingleResource<R, P,>
-  where R: ConstResource, {
+  where R: ConstResource,
+    P: ConstPollicy, {
   /// A constant inital resource pool.
-  pub const INIT: Self = Self {
-    resource: R::INIT,
-    in_use: AtomicBool::new(false,),
-    sync_stack: SyncStack::new(),
-    _data: PhantomData,
-  };
+  #[allow(clippy::declare_interior_mutable_const,)]
+  pub const INIT: Self = Self::with_resource(R::INIT,);
 }
 
 impl<R, P,> SingleResource<R, P,>
-  where R: Resource, {
+  where R: Resource,
+    P: ConstPollicy, {
   /// Creates a new resource pool.
   pub fn new() -> Self { Self::with_resource(R::new(),) }
 }
 
-impl<R, P,> SingleResource<R, P,> {
+impl<R, P,> Default for SingleResource<R, P,>
+  where R: Resource,
+    P: ConstPollicy, {
+  #[inline]
+  fn default() -> Self { Self::new() }
+}
+
+impl<R, Pol,> SingleResource<R, Pol,> {
   /// Creates a new resource pool.
   /// 
   /// # Param
   /// 
   /// resource --- The `Resource` to use.  
-  pub const fn with_resource(resource: R,) -> Self {
+  pub const fn with_resource(resource: R,) -> Self
+    where Pol: ConstPollicy, { Self::with_pollicy(resource, Pol::INIT,) }
+  /// Creates a new resource pool with a reuse pollicy.
+  /// 
+  /// # Param
+  /// 
+  /// resource --- The `Resource` to use.  
+  /// pollicy --- The reuse pollicy to use.  
+  pub const fn with_pollicy(resource: R, pollicy: Pol,) -> Self {
     Self {
-      resource,
+      resource: UnsafeCell::new(resource,),
       in_use: AtomicBool::new(false,),
-      sync_stack: SyncStack::new(),
-      _data: PhantomData,
+      closed: AtomicBool::new(false,),
+      queue: WaitQueue::new(),
+      uses: AtomicUsize::new(0,),
+      created: Created::new(),
+      pollicy,
+      hooks: Hooks::NONE,
+      name: None,
+      stats: PoolStats::new(),
     }
   }
-  /// Gets the resource instance mutably.
+  /// Names the resource pool.
+  /// 
+  /// The name identifies the resource pool in the `registry`, in `tracing` output and in
+  /// deadlock reports.
+  /// 
+  /// # Params
+  /// 
+  /// name --- The name of the resource pool.  
   #[inline]
-  unsafe fn resource_mut(&self,) -> &mut R { &mut *(&self.resource as *const R as *mut R) }
-}
-
-impl<R,> SingleResource<R, Reuse,>
-  where R: Resource, {
-  /// Attempts to aquire the resource and run the closure.
+  pub const fn with_name(mut self, name: &'static str,) -> Self { self.name = Some(name,); self }
+  /// Returns the name of the resource pool.
+  #[inline]
+  pub fn name(&self,) -> Option<&'static str> { self.name }
+  /// Records how long threads wait for the resource and how long it is held in the histograms
+  /// of `stats`.
+  #[inline]
+  pub const fn with_histograms(mut self,) -> Self {
+    self.stats = PoolStats::with_histograms();
+    self
+  }
+  /// Returns statistics about how the resource has been used.
+  #[inline]
+  pub fn stats(&self,) -> &PoolStats { &self.stats }
+  /// Installs callbacks to run as the resource is used.
   /// 
-  /// If the resource could not be locked the closure is returned.
-  fn attempt_aquire<F,>(&self, f: F,) -> Option<F>
-    where F: FnOnce(usize, &mut R,), {
-    struct Finish<'pool, R,> {
-      pool: &'pool SingleResource<R, Reuse,>,
-    }
-
-    impl<R,> Drop for Finish<'_, R,> {
-      fn drop(&mut self,) {
-        //Release the lock.
-        self.pool.in_use.store(false, Ordering::Relaxed,);
-        //Pop a waiting thread from the stack.
-        self.pool.sync_stack.pop();
-      }
-    }
-
-    //Attempt to lock the resource.
-    if self.in_use.compare_and_swap(false, true, Ordering::Acquire,) { return Some(f) }
+  /// # Params
+  /// 
+  /// hooks --- The callbacks to run.  
+  pub const fn with_hooks(mut self, hooks: &'static (dyn PoolHooks<R,> + Sync),) -> Self
+    where R: 'static, {
+    self.hooks = Hooks::new(hooks,);
+    self
+  }
+  /// Consumes the resource pool and returns the resource.
+  #[inline]
+  pub fn into_inner(self,) -> R { self.resource.into_inner() }
+  /// Gets the resource mutably.
+  /// 
+  /// The exclusive borrow guarantees the resource is not in use.
+  #[inline]
+  pub fn get_mut(&mut self,) -> &mut R { self.resource.get_mut() }
+  /// Identifies this resource pool.
+  #[inline]
+  fn pool_id(&self,) -> PoolId { PoolId::of(self, 1,).named(self.name,) }
+  /// Describes how threads wait for the resource.
+  #[inline]
+  fn waiting(&self,) -> Waiting<'_,> {
+    Waiting { closed: Some(&self.closed,), stats: Some(&self.stats,), ..Waiting::DEFAULT }
+  }
+  /// Closes the resource pool.
+  /// 
+  /// Every waiting thread is woken and fails to aquire the resource as does every later
+  /// aquisition; `checked_get_resource` and `get_resource_cancellable` return
+  /// `Err(Closed)` and `get_resource` panics. A thread already holding the resource is
+  /// unaffected.
+  pub fn close(&self,) {
+    self.closed.store(true, Ordering::SeqCst,);
+    self.queue.pop_all();
+  }
+  /// Returns `true` if the resource pool has been closed.
+  #[inline]
+  pub fn is_closed(&self,) -> bool { self.closed.load(Ordering::SeqCst,) }
+  /// Attempts to lock the resource.
+  /// 
+  /// Returns `true` if the resource was locked.
+  fn lock(&self,) -> bool {
+    self.in_use.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed,).is_ok()
+  }
+  /// Unlocks the resource.
+  fn unlock(&self,) {
+    debug_locks::released(self.pool_id(), 0,);
+    //Release the lock, publishing any writes to the resource.
+    self.in_use.store(false, Ordering::Release,);
+    //Wake a waiting thread.
+    self.queue.pop();
+  }
+}
 
-    let _ = Finish { pool: self, };
+/// Checks the reuse pollicy and unlocks the resource when dropped.
+struct Finish<'pool, R, Pol,>
+  where R: Resource,
+    Pol: PolicyKind<R,>, {
+  /// The resource pool the resource belongs to.
+  pool: &'pool SingleResource<R, Pol,>,
+  /// Times how long the resource is held.
+  timer: Timer,
+}
 
-    //Aquired the resource.
-    f(0, unsafe { self.resource_mut() },);
+impl<R, Pol,> Drop for Finish<'_, R, Pol,>
+  where R: Resource,
+    Pol: PolicyKind<R,>, {
+  fn drop(&mut self,) { self.pool.release(self.timer,) }
+}
 
-    None
-  }
+/// A claim on the resource which has not been used yet.
+enum Claim<'pool, R, Pol,>
+  where R: Resource,
+    Pol: PolicyKind<R,>, {
+  /// The resource is locked until the `Finish` is dropped.
+  Locked(Finish<'pool, R, Pol,>,),
+  /// A resource instance taken out of the resource pool.
+  Taken(R,),
 }
 
-impl<R,> SingleResource<R, NoReuse,>
-  where R: Resource, {
-  /// Attempts to aquire the resource and run the closure.
+impl<R, Pol,> SingleResource<R, Pol,>
+  where R: Resource,
+    Pol: PolicyKind<R,>, {
+  /// Returns `true` if the pollicy, hooks or statistics use how long the resource is held.
+  #[inline]
+  pub(crate) fn timed(&self,) -> bool {
+    self.pollicy.timed() || self.hooks.is_installed() || self.stats.has_histograms()
+  }
+  /// Attempts to claim the resource.
   /// 
-  /// If the resource could not be locked the closure is returned.
-  fn attempt_aquire<F,>(&self, f: F,) -> Option<F>
-    where F: FnOnce(usize, &mut R,), {
+  /// Returns `None` if the resource could not be locked.
+  fn attempt_aquire(&self,) -> Option<Claim<'_, R, Pol,>> {
     //Attempt to lock the resource.
-    if self.in_use.compare_and_swap(false, true, Ordering::Acquire,) { return Some(f) }
+    if self.is_closed() || !self.lock() { return None }
 
-    //Aquired the resource.
-    let mut resource = core::mem::replace(unsafe { self.resource_mut() }, R::new(),);
-    //Release the lock.
-    self.in_use.store(false, Ordering::Relaxed,);
-    //Pop a waiting thread from the stack.
-    self.sync_stack.pop();
-    
-    f(0, &mut resource,);
+    self.stats.aquired();
 
-    None
-  }
-}
+    if Pol::DISCARD {
+      //Take the resource instance and let the next caller have a new one.
+      let resource = mem::replace(unsafe { &mut *self.resource.get() }, R::new(),);
+      self.hooks.on_create(0, unsafe { &mut *self.resource.get() },);
+      trace::recreated(self.pool_id(), 0,);
+      self.stats.recreated();
+      //Release the lock.
+      self.unlock();
 
-impl<R, P,> SingleResource<R, Pollicy<P,>,>
-  where R: Resource,
-    P: ReusePollicy<R,>, {
-  /// Attempts to aquire the resource and run the closure.
+      Some(Claim::Taken(resource,),)
+    } else { Some(Claim::Locked(Finish { pool: self, timer: Timer::start_if(self.timed(),), },),) }
+  }
+  /// Runs the closure with a claimed resource.
   /// 
-  /// If the resource could not be locked the closure is returned.
-  fn attempt_aquire<F,>(&self, f: F,) -> Option<F>
+  /// # Params
+  /// 
+  /// claim --- The claim on the resource.  
+  /// f --- The closure to run.  
+  fn run<F,>(&self, claim: Claim<'_, R, Pol,>, f: F,)
     where F: FnOnce(usize, &mut R,), {
-    struct Finish<'a, R, P,>
-      where R: Resource,
-        P: ReusePollicy<R,>, {
-      pool: &'a SingleResource<R, Pollicy<P,>,>,
+    match claim {
+      Claim::Taken(mut resource) => {
+        self.hooks.on_acquire(0, &mut resource,);
+        let timer = Timer::start_if(self.timed(),);
+        {
+          let _holding = trace::holding(self.pool_id(), 0,);
+
+          f(0, &mut resource,);
+        }
+        let held = timer.elapsed();
+
+        self.stats.held(held,);
+        self.hooks.on_release(0, &mut resource, held,);
+        self.hooks.on_discard(0, &mut resource,);
+      },
+      Claim::Locked(_finish) => {
+        debug_locks::aquired(self.pool_id(), 0,);
+
+        let resource = unsafe { &mut *self.resource.get() };
+        let _holding = trace::holding(self.pool_id(), 0,);
+
+        //Aquired the resource.
+        self.hooks.on_acquire(0, resource,);
+        f(0, resource,);
+      },
     }
-
-    impl<R, P,> Drop for Finish<'_, R, P,>
-      where R: Resource,
-        P: ReusePollicy<R,>, {
-      fn drop(&mut self,) {
-        //Check if the resource should be reused.
-        let resource = unsafe { self.pool.resource_mut() };
-        if !P::reuse(resource,) { *resource = R::new() }
-        
-        //Release the lock.
-        self.pool.in_use.store(false, Ordering::Relaxed,);
-        //Pop a waiting thread from the stack.
-        self.pool.sync_stack.pop();
-      }
+  }
+  /// Checks the reuse pollicy and unlocks the resource after it has been used.
+  /// 
+  /// # Params
+  /// 
+  /// timer --- Timed how long the resource was held.  
+  fn release(&self, timer: Timer,) {
+    let usage = Usage {
+      id: 0,
+      uses: self.uses.fetch_add(1, Ordering::Relaxed,) + 1,
+      held: timer.elapsed(),
+      age: unsafe { self.created.age(timer,) },
+    };
+
+    //Check if the resource should be reused.
+    let resource = unsafe { &mut *self.resource.get() };
+    self.stats.held(usage.held,);
+    self.hooks.on_release(0, resource, usage.held,);
+    if !self.pollicy.reuse(resource, &usage,) {
+      self.hooks.replace(0, resource,);
+      trace::recreated(self.pool_id(), 0,);
+      self.stats.recreated();
+      self.uses.store(0, Ordering::Relaxed,);
+      unsafe { self.created.reset(self.timed(),); }
     }
 
-    //Attempt to lock the resource.
-    if self.in_use.compare_and_swap(false, true, Ordering::Acquire,) { return Some(f) }
-
-    let _ = Finish { pool: self, };
-    //Aquired the resource.
-    let resource = unsafe { self.resource_mut() };
-    f(0, resource,);
-
-    None
+    //Release the lock.
+    self.unlock();
   }
-}
-
-unsafe impl<R,> ResourcePool for SingleResource<R, NoReuse,>
-  where R: Resource, {
-  type Resource = R;
-
-  #[inline]
-  fn try_get_resource<F,>(&self, f: F,) -> bool
-    where F: FnOnce(usize, &mut Self::Resource,), {
-    //Attempt to aquire the resource.
-    let aquired = self.attempt_aquire(f,).is_none();
-
-    //If we aquired the resource allow another a chance to.
-    if aquired { self.sync_stack.pop(); }
-
-    aquired
+  /// Gets the resource unless the resource pool is closed.
+  /// 
+  /// Returns `Err(Closed)` without running the closure if the resource pool was closed
+  /// before the resource was aquired.
+  /// 
+  /// # Params
+  /// 
+  /// f --- The closure to run once the resource is aquired.  
+  pub fn checked_get_resource<P, F,>(&self, f: F,) -> Result<(), PoolError>
+    where P: Park,
+      F: FnOnce(usize, &mut R,), {
+    //Attempt to aquire the resource, waiting for it to be released between attempts.
+    self.queue.wait_with::<P, _, _, _,>(
+      self.waiting(), || self.attempt_aquire(),
+      || { self.queue.pop(); },
+      self.pool_id(), Wait::Any(1,),
+    ).map(|claim,| self.run(claim, f,),)
   }
-  fn get_resource<P, F,>(&self, mut f: F,)
+  /// Gets the resource unless `token` is cancelled first.
+  /// 
+  /// Returns `Err(Cancelled)` without running the closure if `token` was cancelled
+  /// before the resource was aquired.
+  /// 
+  /// # Params
+  /// 
+  /// token --- Cancels the aquisition.  
+  /// f --- The closure to run once the resource is aquired.  
+  pub fn get_resource_cancellable<P, F,>(&self, token: &CancelToken, f: F,) -> Result<(), PoolError>
     where P: Park,
-      F: FnOnce(usize, &mut Self::Resource,), {
-    loop {
-      //Attempt to aquire the resource.
-      match self.attempt_aquire(f,) {
-        //Failed to aquire the resource.
-        Some(ret) => {
-          //Return the resource for the next attempt.
-          f = ret;
-          //Wait for the resource to become available.
-          self.sync_stack.park::<P,>();
-        },
-        //We aquired the resource.
-        None => { self.sync_stack.pop(); break },
-      }
-    }
+      F: FnOnce(usize, &mut R,), {
+    //Attempt to aquire the resource, waiting for it to be released between attempts.
+    self.queue.wait_with::<P, _, _, _,>(
+      Waiting { cancel: Some(token,), ..self.waiting() },
+      || self.attempt_aquire(),
+      || { self.queue.pop(); },
+      self.pool_id(), Wait::Any(1,),
+    ).map(|claim,| self.run(claim, f,),)
+  }
+  /// Closes the resource pool and waits for the resource to be released.
+  /// 
+  /// Returns the resource, leaving a new instance in the resource pool.
+  pub fn drain<P,>(&self,) -> R
+    where P: Park, {
+    self.close();
+    //Attempt to take the resource, waiting for it to be released between attempts.
+    self.queue.wait::<P, _, _, _,>(
+      || if self.lock() {
+        let resource = mem::replace(unsafe { &mut *self.resource.get() }, R::new(),);
+
+        unsafe { self.created.reset(self.timed(),); }
+        self.unlock();
+        Some(resource,)
+      } else { None },
+      || { self.queue.pop(); },
+      self.pool_id(), Wait::Any(1,),
+    )
   }
 }
 
-unsafe impl<R,> ResourcePool for SingleResource<R, Reuse,>
-  where R: Resource, {
+unsafe impl<R, Pol,> ResourcePool for SingleResource<R, Pol,>
+  where R: Resource,
+    Pol: PolicyKind<R,>, {
   type Resource = R;
 
   #[inline]
   fn try_get_resource<F,>(&self, f: F,) -> bool
     where F: FnOnce(usize, &mut Self::Resource,), {
     //Attempt to aquire the resource.
-    let aquired = self.attempt_aquire(f,).is_none();
-
-    //If we aquired the resource allow another a chance to.
-    if aquired { self.sync_stack.pop(); }
-
-    aquired
+    match self.attempt_aquire() {
+      Some(claim) => { self.run(claim, f,); true },
+      None => false,
+    }
   }
-  fn get_resource<P, F,>(&self, mut f: F,)
+  /// Panics if the resource pool is closed; see `checked_get_resource`.
+  #[inline]
+  fn get_resource<P, F,>(&self, f: F,)
     where P: Park,
       F: FnOnce(usize, &mut Self::Resource,), {
-    loop {
-      //Attempt to aquire the resource.
-      match self.attempt_aquire(f,) {
-        //Failed to aquire the resource.
-        Some(ret) => {
-          //Return the resource for the next attempt.
-          f = ret;
-          //Wait for the resource to become available.
-          self.sync_stack.park::<P,>();
-        },
-        //We aquired the resource.
-        None => { self.sync_stack.pop(); break },
-      }
-    }
+    if let Err(error) = self.checked_get_resource::<P, F,>(f,) { panic!("{}", error,) }
   }
 }
 
-unsafe impl<R, Pol,> ResourcePool for SingleResource<R, Pollicy<Pol,>,>
-  where R: Resource,
-    Pol: ReusePollicy<R,>, {
-  type Resource = R;
-
-  #[inline]
-  fn try_get_resource<F,>(&self, f: F,) -> bool
-    where F: FnOnce(usize, &mut Self::Resource,), {
-    //Attempt to aquire the resource.
-    let aquired = self.attempt_aquire(f,).is_none();
-
-    //If we aquired the resource allow another a chance to.
-    if aquired { self.sync_stack.pop(); }
-
-    aquired
-  }
-  fn get_resource<P, F,>(&self, mut f: F,)
-    where P: Park,
-      F: FnOnce(usize, &mut Self::Resource,), {
-    loop {
-      //Attempt to aquire the resource.
-      match self.attempt_aquire(f,) {
-        //Failed to aquire the resource.
-        Some(ret) => {
-          //Return the resource for the next attempt.
-          f = ret;
-          //Wait for the resource to become available.
-          self.sync_stack.park::<P,>();
-        },
-        //We aquired the resource.
-        None => { self.sync_stack.pop(); break },
-      }
+impl<R, Pol,> Introspect for SingleResource<R, Pol,>
+  where Self: Sync, {
+  fn info(&self,) -> PoolInfo {
+    PoolInfo {
+      name: self.pool_id().name,
+      size: 1,
+      in_use: self.in_use.load(Ordering::Relaxed,) as usize,
+      waiters: self.queue.len(),
     }
   }
+  #[inline]
+  fn stats(&self,) -> Option<&PoolStats> { Some(&self.stats,) }
 }
 
 #[cfg(test,)]
@@ -273,7 +394,103 @@ mod tests {
     let resource = SingleResource::<i32, NoReuse,>::new();
 
     resource.get_resource::<Thread, _,>(|_, r,| *r = 1,);
-    resource.get_resource::<Thread, _,>(|_, r,| assert_eq!(*r, 0,),);
+    resource.get_resource::<Thread, _,>(|_, r,| {
+      assert_eq!(*r, 0,);
+      //The resource is not held while a `NoReuse` instance is in use.
+      assert!(resource.try_get_resource(|_, r,| assert_eq!(*r, 0,),),);
+    },);
+  }
+  #[test]
+  fn test_single_resource_close() {
+    use std::{thread, sync::Arc, vec::Vec,};
+
+    let resource = Arc::new(SingleResource::<Vec<i32>, Reuse,>::new(),);
+    let mut drain = None;
+
+    resource.get_resource::<Thread, _,>(|_, r,| {
+      let pool = resource.clone();
+      let waiter = thread::spawn(move || {
+        pool.get_resource_cancellable::<Thread, _,>(&CancelToken::new(), |_, _,| unreachable!(),)
+      },);
+
+      while resource.queue.len() == 0 { thread::yield_now(); }
+      resource.close();
+      //Waiting threads are woken.
+      assert_eq!(waiter.join().unwrap(), Err(PoolError::Closed),);
+
+      let pool = resource.clone();
+
+      //Draining waits for the resource to be released.
+      drain = Some(thread::spawn(move || pool.drain::<Thread>(),),);
+      r.push(1,);
+    },);
+
+    assert_eq!(drain.unwrap().join().unwrap(), [1,],);
+    assert!(!resource.try_get_resource(|_, _,| unreachable!(),),);
+    assert_eq!(
+      resource.get_resource_cancellable::<Thread, _,>(&CancelToken::new(), |_, _,| unreachable!(),),
+      Err(PoolError::Closed),
+    );
+    assert_eq!(
+      resource.checked_get_resource::<Thread, _,>(|_, _,| unreachable!(),),
+      Err(PoolError::Closed),
+    );
+    assert_eq!(resource.stats().failures(PoolError::Closed,), 3,);
+    assert_eq!(resource.stats().failures(PoolError::Cancelled,), 0,);
+  }
+  #[test]
+  fn test_single_resource_exclusive() {
+    let mut resource = SingleResource::<i32, Reuse,>::new();
+
+    *resource.get_mut() = 1;
+    resource.get_resource::<Thread, _,>(|_, r,| { assert_eq!(*r, 1,); *r = 2; },);
+    assert_eq!(resource.into_inner(), 2,);
+  }
+  #[test]
+  fn test_single_resource_hooks() {
+    use std::{sync::Mutex, time::Duration, vec::Vec,};
+
+    /// Records the hooks which were run and the value of the resource.
+    struct Log(Mutex<Vec<(&'static str, i32,)>>,);
+
+    impl Log {
+      fn push(&self, id: usize, hook: &'static str, resource: &i32,) {
+        assert_eq!(id, 0,);
+        self.0.lock().unwrap().push((hook, *resource,),);
+      }
+      fn take(&self,) -> Vec<(&'static str, i32,)> { std::mem::take(&mut self.0.lock().unwrap(),) }
+    }
+
+    impl PoolHooks<i32,> for Log {
+      fn on_acquire(&self, id: usize, r: &mut i32,) { self.push(id, "acquire", r,) }
+      fn on_release(&self, id: usize, r: &mut i32, _: Duration,) { self.push(id, "release", r,) }
+      fn on_create(&self, id: usize, r: &mut i32,) { self.push(id, "create", r,) }
+      fn on_discard(&self, id: usize, r: &mut i32,) { self.push(id, "discard", r,) }
+    }
+
+    static LOG: Log = Log(Mutex::new(Vec::new(),),);
+
+    let log = &LOG;
+    let resource = SingleResource::<i32, _,>::with_pollicy(0, Pollicy(MaxUses(2,),),)
+      .with_hooks(log,);
+
+    resource.get_resource::<Thread, _,>(|_, r,| *r += 10,);
+    assert_eq!(log.take(), [("acquire", 0,), ("release", 10,),],);
+    resource.get_resource::<Thread, _,>(|_, r,| *r += 10,);
+    assert_eq!(
+      log.take(),
+      [("acquire", 10,), ("release", 20,), ("discard", 20,), ("create", 0,),],
+    );
+
+    static RESOURCE: SingleResource<i32, NoReuse,> = SingleResource::INIT.with_hooks(&LOG,);
+
+    let resource = &RESOURCE;
+
+    resource.get_resource::<Thread, _,>(|_, r,| *r += 10,);
+    assert_eq!(
+      log.take(),
+      [("create", 0,), ("acquire", 0,), ("release", 10,), ("discard", 10,),],
+    );
   }
   #[test]
   fn test_single_resource_multithread() {
@@ -294,4 +511,35 @@ mod tests {
     resource.get_resource::<Thread, _,>(|_, r,| *r = 1,);
     resource.get_resource::<Thread, _,>(|_, r,| assert_eq!(*r, 0,),);
   }
+  #[test]
+  fn test_single_resource_pollicy() {
+    /// Discards a resource after two uses or if it was held for a second.
+    struct Twice;
+
+    impl ReusePollicy<i32,> for Twice {
+      fn reuse(&self, _: &mut i32, usage: &Usage,) -> bool {
+        assert_eq!(usage.id, 0,);
+
+        usage.uses < 2 && usage.held.as_secs() == 0
+      }
+    }
+
+    let resource = SingleResource::<i32, _,>::with_pollicy(0, Pollicy(Twice,),);
+
+    resource.get_resource::<Thread, _,>(|_, r,| *r = 1,);
+    resource.get_resource::<Thread, _,>(|_, r,| { assert_eq!(*r, 1,); *r = 2; },);
+    resource.get_resource::<Thread, _,>(|_, r,| { assert_eq!(*r, 0,); *r = 3; },);
+    resource.get_resource::<Thread, _,>(|_, r,| assert_eq!(*r, 3,),);
+
+    #[cfg(feature = "std",)] {
+      use std::{thread, time::Duration,};
+
+      resource.get_resource::<Thread, _,>(|_, r,| *r = 4,);
+      resource.get_resource::<Thread, _,>(|_, r,| {
+        assert_eq!(*r, 4,);
+        thread::sleep(Duration::from_millis(1100,),);
+      },);
+      resource.get_resource::<Thread, _,>(|_, r,| assert_eq!(*r, 0,),);
+    }
+  }
 }
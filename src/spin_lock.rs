@@ -0,0 +1,63 @@
+//! Defines the spin lock protecting the internal state of resource pools.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-18
+
+use core::{
+  hint,
+  cell::UnsafeCell,
+  sync::atomic::{AtomicBool, Ordering,},
+};
+
+/// A lock which spins until it is free.
+/// 
+/// Unlike the resource pools it is not traced, checked for deadlocks or counted in any
+/// statistics so it only suits state which is locked briefly.
+pub(crate) struct SpinLock<T,> {
+  /// A flag indicating if `value` is currently locked.
+  locked: AtomicBool,
+  /// The protected value.
+  value: UnsafeCell<T>,
+}
+
+unsafe impl<T,> Sync for SpinLock<T,>
+  where T: Send, {}
+
+impl<T,> SpinLock<T,> {
+  /// Creates an unlocked lock.
+  /// 
+  /// # Params
+  /// 
+  /// value --- The value to protect.  
+  #[inline]
+  pub const fn new(value: T,) -> Self {
+    Self { locked: AtomicBool::new(false,), value: UnsafeCell::new(value,), }
+  }
+  /// Runs `f` with the value locked.
+  /// 
+  /// # Params
+  /// 
+  /// f --- The closure to run.  
+  pub fn with<F, R,>(&self, f: F,) -> R
+    where F: FnOnce(&mut T,) -> R, {
+    while self.locked.compare_exchange_weak(
+      false, true, Ordering::Acquire, Ordering::Relaxed,
+    ).is_err() { hint::spin_loop(); }
+
+    //Unlocks the value even if `f` panics.
+    let _unlock = Unlock(&self.locked,);
+
+    f(unsafe { &mut *self.value.get() },)
+  }
+  /// Returns the value without locking it.
+  #[inline]
+  pub fn get_mut(&mut self,) -> &mut T { self.value.get_mut() }
+}
+
+/// Unlocks a `SpinLock` when dropped.
+struct Unlock<'a,>(&'a AtomicBool,);
+
+impl Drop for Unlock<'_,> {
+  #[inline]
+  fn drop(&mut self,) { self.0.store(false, Ordering::Release,); }
+}
@@ -0,0 +1,337 @@
+//! Defines the statistics resource pools keep about how they are used.
+//! 
+//! Every resource pool counts its aquisitions, failures and recreations. Resource pools
+//! created `with_histograms` also record how long threads waited for resources and how
+//! long resources were held in a `Histogram` each.
+//! 
+//! ```rust
+//! use bottleneck::*;
+//! use std::thread::Thread;
+//! 
+//! let pool = MultiResource::<i32>::new_resources(4).with_histograms();
+//! 
+//! for _ in 0..100 { pool.get_resource::<Thread, _>(|_, _,| {}); }
+//! 
+//! let held = pool.stats().hold_time().take();
+//! 
+//! assert_eq!(held.count(), 100);
+//! assert!(held.percentile(99.0).is_some());
+//! assert_eq!(pool.stats().hold_time().count(), 0);
+//! ```
+//! 
+//! Without the `std` feature durations are not measured and are recorded as zero.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-18
+
+use crate::PoolError;
+use core::{
+  time::Duration,
+  sync::atomic::{AtomicUsize, Ordering,},
+};
+
+/// The number of buckets in a `Histogram` with a finite upper bound.
+pub const BUCKETS: usize = 32;
+
+/// Totals durations in microseconds.
+#[cfg(target_has_atomic = "64",)]
+type AtomicSum = core::sync::atomic::AtomicU64;
+/// Totals durations in microseconds.
+/// 
+/// Targets without 64 bit atomics total durations in a `usize` which can wrap.
+#[cfg(not(target_has_atomic = "64",),)]
+type AtomicSum = AtomicUsize;
+
+/// Counts durations in buckets whose bounds grow in powers of two.
+/// 
+/// Bucket `i` counts the durations no longer than `2^i` microseconds and the last bucket
+/// counts every longer duration. Recording a duration is lock-free.
+pub struct Histogram {
+  /// The number of durations recorded in each bucket.
+  buckets: [AtomicUsize; BUCKETS + 1],
+  /// The total of the recorded durations in microseconds.
+  sum: AtomicSum,
+}
+
+impl Histogram {
+  /// Creates an empty histogram.
+  pub const fn new() -> Self {
+    Self {
+      buckets: [const { AtomicUsize::new(0,) }; BUCKETS + 1],
+      sum: AtomicSum::new(0,),
+    }
+  }
+  /// Records a duration.
+  /// 
+  /// # Params
+  /// 
+  /// duration --- The duration to record.  
+  pub fn record(&self, duration: Duration,) {
+    let micros = duration.as_micros().min(u64::MAX as u128,) as u64;
+    //The smallest `i` such that `micros <= 2^i`.
+    let bucket = (u64::BITS - micros.saturating_sub(1,).leading_zeros()) as usize;
+
+    self.buckets[bucket.min(BUCKETS,)].fetch_add(1, Ordering::Relaxed,);
+    self.sum.fetch_add(micros as _, Ordering::Relaxed,);
+  }
+  /// Returns the upper bound of the bucket `bucket`.
+  /// 
+  /// The last bucket has no upper bound and returns `None`.
+  /// 
+  /// # Params
+  /// 
+  /// bucket --- The index of the bucket.  
+  pub fn bound(bucket: usize,) -> Option<Duration> {
+    if bucket < BUCKETS { Some(Duration::from_micros(1 << bucket,),) } else { None }
+  }
+  /// Returns the number of durations recorded in each bucket.
+  #[inline]
+  pub fn buckets(&self,) -> [usize; BUCKETS + 1] { self.snapshot().buckets }
+  /// Returns the number of recorded durations.
+  pub fn count(&self,) -> usize {
+    self.buckets.iter().map(|bucket,| bucket.load(Ordering::Relaxed,),).sum()
+  }
+  /// Returns the total of the recorded durations.
+  pub fn sum(&self,) -> Duration {
+    Duration::from_micros(self.sum_micros(false,),)
+  }
+  /// Returns an upper bound of the `p`th percentile of the recorded durations.
+  /// 
+  /// See `HistogramSnapshot::percentile`.
+  /// 
+  /// # Params
+  /// 
+  /// p --- The percentile between `0` and `100`.  
+  #[inline]
+  pub fn percentile(&self, p: f64,) -> Option<Duration> { self.snapshot().percentile(p,) }
+  /// Copies the recorded durations.
+  /// 
+  /// Durations recorded while the snapshot is taken may be partly included.
+  pub fn snapshot(&self,) -> HistogramSnapshot {
+    let mut buckets = [0; BUCKETS + 1];
+
+    for (count, bucket,) in buckets.iter_mut().zip(self.buckets.iter(),) {
+      *count = bucket.load(Ordering::Relaxed,);
+    }
+
+    HistogramSnapshot { buckets, sum: self.sum_micros(false,), }
+  }
+  /// Removes and returns the recorded durations.
+  /// 
+  /// Every duration is either returned or remains recorded, even if it is recorded while
+  /// the histogram is being taken.
+  pub fn take(&self,) -> HistogramSnapshot {
+    let mut buckets = [0; BUCKETS + 1];
+
+    for (count, bucket,) in buckets.iter_mut().zip(self.buckets.iter(),) {
+      *count = bucket.swap(0, Ordering::Relaxed,);
+    }
+
+    HistogramSnapshot { buckets, sum: self.sum_micros(true,), }
+  }
+  /// Returns the total of the recorded durations in microseconds.
+  /// 
+  /// # Params
+  /// 
+  /// take --- If `true` the total is reset to `0`.  
+  #[allow(clippy::unnecessary_cast,)]
+  fn sum_micros(&self, take: bool,) -> u64 {
+    //The total is a `usize` on targets without 64 bit atomics.
+    if take { self.sum.swap(0, Ordering::Relaxed,) as u64 }
+    else { self.sum.load(Ordering::Relaxed,) as u64 }
+  }
+  /// Removes the recorded durations.
+  #[inline]
+  pub fn reset(&self,) { self.take(); }
+}
+
+impl Default for Histogram {
+  #[inline]
+  fn default() -> Self { Self::new() }
+}
+
+/// A copy of the durations recorded by a `Histogram`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug,)]
+pub struct HistogramSnapshot {
+  /// The number of durations recorded in each bucket.
+  buckets: [usize; BUCKETS + 1],
+  /// The total of the recorded durations in microseconds.
+  sum: u64,
+}
+
+impl HistogramSnapshot {
+  /// Returns the number of durations recorded in each bucket.
+  #[inline]
+  pub fn buckets(&self,) -> [usize; BUCKETS + 1] { self.buckets }
+  /// Returns the number of recorded durations.
+  #[inline]
+  pub fn count(&self,) -> usize { self.buckets.iter().sum() }
+  /// Returns the total of the recorded durations.
+  #[inline]
+  pub fn sum(&self,) -> Duration { Duration::from_micros(self.sum,) }
+  /// Returns an upper bound of the `p`th percentile of the recorded durations.
+  /// 
+  /// The bound is the upper bound of the bucket holding the percentile so it is at most
+  /// twice the real percentile. Returns `None` if no durations were recorded and
+  /// `Duration::MAX` if the percentile is in the last bucket.
+  /// 
+  /// # Params
+  /// 
+  /// p --- The percentile between `0` and `100`.  
+  pub fn percentile(&self, p: f64,) -> Option<Duration> {
+    assert!((0.0..=100.0).contains(&p,), "the percentile {} is not between 0 and 100", p,);
+
+    let count = self.count();
+    if count == 0 { return None }
+
+    //The number of durations no longer than the percentile.
+    let rank = ((p / 100.0 * count as f64).ceil() as usize).max(1,);
+    let mut seen = 0;
+
+    for (bucket, &value,) in self.buckets.iter().enumerate() {
+      seen += value;
+      if seen >= rank { return Some(Histogram::bound(bucket,).unwrap_or(Duration::MAX,),) }
+    }
+
+    Some(Duration::MAX)
+  }
+}
+
+/// Statistics about how a resource pool has been used.
+pub struct PoolStats {
+  /// The number of times resources were aquired.
+  acquisitions: AtomicUsize,
+  /// The number of aquisitions which failed with each `PoolError`.
+  failures: [AtomicUsize; PoolError::ALL.len()],
+  /// The number of resources recreated by the reuse pollicy.
+  recreations: AtomicUsize,
+  /// If `true` durations are recorded in `wait_time` and `hold_time`.
+  histograms: bool,
+  /// How long aquisitions waited for resources.
+  wait_time: Histogram,
+  /// How long resources were held.
+  hold_time: Histogram,
+}
+
+impl PoolStats {
+  /// Creates empty statistics.
+  pub const fn new() -> Self {
+    Self {
+      acquisitions: AtomicUsize::new(0,),
+      failures: [const { AtomicUsize::new(0,) }; PoolError::ALL.len()],
+      recreations: AtomicUsize::new(0,),
+      histograms: false,
+      wait_time: Histogram::new(),
+      hold_time: Histogram::new(),
+    }
+  }
+  /// Creates empty statistics which record durations.
+  pub(crate) const fn with_histograms() -> Self { Self { histograms: true, ..Self::new() } }
+  /// Returns the number of times resources were aquired.
+  /// 
+  /// Aquiring several resources at once counts as one aquisition.
+  #[inline]
+  pub fn acquisitions(&self,) -> usize { self.acquisitions.load(Ordering::Relaxed,) }
+  /// Returns the number of aquisitions which stopped waiting without a resource because
+  /// of `error`.
+  /// 
+  /// # Params
+  /// 
+  /// error --- The reason the aquisitions failed.  
+  #[inline]
+  pub fn failures(&self, error: PoolError,) -> usize {
+    self.failures[error as usize].load(Ordering::Relaxed,)
+  }
+  /// Returns the number of resources recreated by the reuse pollicy.
+  #[inline]
+  pub fn recreations(&self,) -> usize { self.recreations.load(Ordering::Relaxed,) }
+  /// Returns `true` if durations are recorded in `wait_time` and `hold_time`.
+  #[inline]
+  pub fn has_histograms(&self,) -> bool { self.histograms }
+  /// Returns how long aquisitions waited for resources.
+  /// 
+  /// Only aquisitions which were allowed to wait are recorded and only if the resource
+  /// pool was created `with_histograms`.
+  #[inline]
+  pub fn wait_time(&self,) -> &Histogram { &self.wait_time }
+  /// Returns how long resources were held.
+  /// 
+  /// Durations are only recorded if the resource pool was created `with_histograms`.
+  #[inline]
+  pub fn hold_time(&self,) -> &Histogram { &self.hold_time }
+  /// Records an aquisition.
+  #[inline]
+  pub(crate) fn aquired(&self,) { self.acquisitions.fetch_add(1, Ordering::Relaxed,); }
+  /// Records an aquisition which stopped waiting.
+  /// 
+  /// # Params
+  /// 
+  /// error --- The reason the aquisition failed.  
+  #[inline]
+  pub(crate) fn failed(&self, error: PoolError,) {
+    self.failures[error as usize].fetch_add(1, Ordering::Relaxed,);
+  }
+  /// Records a recreated resource.
+  #[inline]
+  pub(crate) fn recreated(&self,) { self.recreations.fetch_add(1, Ordering::Relaxed,); }
+  /// Records how long an aquisition waited for resources.
+  /// 
+  /// # Params
+  /// 
+  /// waited --- How long the aquisition waited.  
+  #[inline]
+  pub(crate) fn waited(&self, waited: Duration,) {
+    if self.histograms { self.wait_time.record(waited,) }
+  }
+  /// Records how long resources were held.
+  /// 
+  /// # Params
+  /// 
+  /// held --- How long the resources were held.  
+  #[inline]
+  pub(crate) fn held(&self, held: Duration,) {
+    if self.histograms { self.hold_time.record(held,) }
+  }
+}
+
+impl Default for PoolStats {
+  #[inline]
+  fn default() -> Self { Self::new() }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_histogram() {
+    let histogram = Histogram::new();
+
+    for &micros in [0, 1, 2, 3, 4, 5, 1 << 40,].iter() {
+      histogram.record(Duration::from_micros(micros,),);
+    }
+
+    let buckets = histogram.buckets();
+
+    assert_eq!(buckets[..4], [2, 1, 2, 1,],);
+    assert_eq!(buckets[BUCKETS], 1,);
+    assert_eq!(histogram.count(), 7,);
+    assert_eq!(histogram.sum(), Duration::from_micros(15 + (1 << 40),),);
+    assert_eq!(Histogram::bound(3,), Some(Duration::from_micros(8,),),);
+    assert_eq!(Histogram::bound(BUCKETS,), None,);
+
+    let snapshot = histogram.snapshot();
+
+    assert_eq!(snapshot.percentile(0.0,), Some(Duration::from_micros(1,),),);
+    assert_eq!(snapshot.percentile(50.0,), Some(Duration::from_micros(4,),),);
+    assert_eq!(snapshot.percentile(80.0,), Some(Duration::from_micros(8,),),);
+    assert_eq!(snapshot.percentile(100.0,), Some(Duration::MAX,),);
+    assert_eq!(histogram.take(), snapshot,);
+    assert_eq!(histogram.count(), 0,);
+    assert_eq!(histogram.percentile(50.0,), None,);
+
+    histogram.record(Duration::from_micros(3,),);
+    histogram.reset();
+    assert_eq!(histogram.sum(), Duration::from_secs(0,),);
+  }
+}
@@ -0,0 +1,309 @@
+//! Defines a `MultiResource` which caches a released resource in each thread.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-18
+
+use super::*;
+use crate::{pollicy::*, trace, debug_locks::{self, Wait,}, wait_queue::Waiting,};
+use core::sync::atomic::{self, AtomicBool, Ordering,};
+use std::{cell::RefCell, sync::Arc, vec::Vec,};
+
+/// Returns a cached resource to its resource pool.
+trait Flush {
+  /// Returns the cached resource `id` to the resource pool if it was not stolen.
+  fn flush(&self, id: usize,);
+}
+
+/// A resource cached by the current thread.
+struct Entry {
+  /// The resource pool the resource belongs to.
+  pool: Arc<dyn Flush>,
+  /// The Id of the cached resource or `None` once it has been taken.
+  id: Option<usize>,
+}
+
+impl Entry {
+  /// Returns the address of the resource pool.
+  #[inline]
+  fn key(&self,) -> usize { Arc::as_ptr(&self.pool,) as *const () as usize }
+}
+
+impl Drop for Entry {
+  fn drop(&mut self,) {
+    if let Some(id) = self.id { self.pool.flush(id,) }
+  }
+}
+
+std::thread_local! {
+  /// The resources cached by the current thread.
+  /// 
+  /// Dropping the cache at thread exit returns every cached resource.
+  static CACHE: RefCell<Vec<Entry>> = const { RefCell::new(Vec::new(),) };
+}
+
+/// The state shared between a `ThreadCached` and the threads caching its resources.
+struct Shared<R, Pol,> {
+  /// The resource pool.
+  pool: MultiResource<R, Pol,>,
+  /// A flag for each resource indicating if it is cached by a thread.
+  cached: Vec<AtomicBool>,
+}
+
+impl<R, Pol,> Shared<R, Pol,> {
+  /// Attempts to take the cached resource `id`.
+  /// 
+  /// Returns `false` if the resource is not cached.
+  #[inline]
+  fn claim(&self, id: usize,) -> bool {
+    self.cached[id].compare_exchange(true, false, Ordering::SeqCst, Ordering::Relaxed,).is_ok()
+  }
+  /// Attempts to take a resource cached by any thread.
+  fn steal(&self,) -> Option<usize> {
+    //Pairs with the fence in `ThreadCached::cache`.
+    atomic::fence(Ordering::SeqCst,);
+
+    (0..self.cached.len()).find(|&id,| self.claim(id,),)
+  }
+}
+
+impl<R, Pol,> Flush for Shared<R, Pol,> {
+  fn flush(&self, id: usize,) {
+    if self.claim(id,) { self.pool.release_resource(id,) }
+  }
+}
+
+/// Checks the reuse pollicy and caches a claimed resource when dropped.
+struct Finish<'pool, R, Pol,>
+  where R: 'static + Resource,
+    Pol: 'static + PolicyKind<R,>, {
+  /// The Id of the resource.
+  resource: usize,
+  /// Times how long the resource is held.
+  timer: Timer,
+  /// The resource pool the resource belongs to.
+  pool: &'pool ThreadCached<R, Pol,>,
+}
+
+impl<R, Pol,> Drop for Finish<'_, R, Pol,>
+  where R: 'static + Resource,
+    Pol: 'static + PolicyKind<R,>, {
+  fn drop(&mut self,) {
+    self.pool.shared.pool.recycle(self.resource, self.resource, self.timer,);
+    self.pool.cache(self.resource,);
+  }
+}
+
+/// A `MultiResource` which keeps the last resource each thread released in a thread
+/// local cache.
+/// 
+/// A thread reuses its cached resource without locking the shared list of available
+/// resources. Threads which cannot get a resource from the resource pool steal resources
+/// cached by other threads so a cached resource is never unavailable.
+/// 
+/// Cached resources are returned to the resource pool when their thread exits.
+/// 
+/// ```rust
+/// use bottleneck::*;
+/// use std::thread::Thread;
+/// 
+/// let pool = ThreadCached::new(MultiResource::<Vec<u8>,>::new_resources(4,),);
+/// 
+/// let mut first = None;
+/// 
+/// pool.get_resource::<Thread, _,>(|id, _,| first = Some(id,),);
+/// //This thread reuses its cached resource.
+/// pool.get_resource::<Thread, _,>(|id, _,| assert_eq!(Some(id,), first,),);
+/// ```
+pub struct ThreadCached<R, Pollicy = Reuse,> {
+  /// The resource pool and cached flags.
+  shared: Arc<Shared<R, Pollicy,>>,
+}
+
+impl<R, Pol,> ThreadCached<R, Pol,>
+  where R: 'static + Resource,
+    Pol: 'static + PolicyKind<R,>, {
+  /// Creates a thread caching resource pool.
+  /// 
+  /// # Params
+  /// 
+  /// pool --- The resource pool to cache resources from.  
+  pub fn new(pool: MultiResource<R, Pol,>,) -> Self {
+    let cached = (0..pool.len()).map(|_,| AtomicBool::new(false,),).collect();
+
+    Self { shared: Arc::new(Shared { pool, cached, },), }
+  }
+  /// Returns the address of the shared state.
+  #[inline]
+  fn key(&self,) -> usize { Arc::as_ptr(&self.shared,) as *const () as usize }
+  /// Takes the resource cached by the current thread.
+  fn take_cached(&self,) -> Option<usize> {
+    let key = self.key();
+    let id = CACHE.try_with(|cache,| {
+      let mut cache = cache.borrow_mut();
+      let index = cache.iter().position(|entry,| entry.key() == key,)?;
+
+      cache.swap_remove(index,).id.take()
+    },).ok()??;
+
+    //The resource may have been stolen.
+    if self.shared.claim(id,) { Some(id) } else { None }
+  }
+  /// Caches a used resource in the current thread or returns it to the resource pool.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The Id of the resource to cache.  
+  fn cache(&self, id: usize,) {
+    let key = self.key();
+    let cached = CACHE.try_with(|cache,| {
+      let mut cache = cache.borrow_mut();
+
+      //Only the most recently released resource is cached.
+      if cache.iter().any(|entry,| entry.key() == key,) { return false }
+
+      debug_locks::released(self.shared.pool.pool_id(), id,);
+      self.shared.cached[id].store(true, Ordering::SeqCst,);
+      cache.push(Entry { pool: self.shared.clone(), id: Some(id,), },);
+
+      true
+    },).unwrap_or(false,);
+
+    if !cached { return self.shared.pool.release_resource(id,) }
+
+    //Pairs with the fence in `Shared::steal`.
+    atomic::fence(Ordering::SeqCst,);
+    //Let a waiting thread steal the resource.
+    if self.shared.pool.queue.len() != 0 { self.shared.pool.queue.pop(); }
+  }
+  /// Claims the resource `id`.
+  /// 
+  /// # Params
+  /// 
+  /// id --- The Id of the aquired resource.  
+  fn claim(&self, id: usize,) -> Finish<'_, R, Pol,> {
+    self.shared.pool.stats.aquired();
+
+    Finish { resource: id, timer: Timer::start_if(self.shared.pool.timed(),), pool: self, }
+  }
+  /// Runs the closure using a claimed resource.
+  /// 
+  /// # Params
+  /// 
+  /// finish --- Caches or releases the claimed resource.  
+  /// f --- The closure to run.  
+  fn run<F,>(&self, finish: Finish<'_, R, Pol,>, f: F,)
+    where F: FnOnce(usize, &mut R,), {
+    let pool = &self.shared.pool;
+    let id = finish.resource;
+
+    debug_locks::aquired(pool.pool_id(), id,);
+
+    let instance = unsafe { &mut *pool.resource(id,) };
+    let _holding = trace::holding(pool.pool_id(), id,);
+
+    pool.hooks.on_acquire(id, instance,);
+    f(id, instance,);
+  }
+  /// Attempts to claim a resource from the resource pool or another thread's cache.
+  /// 
+  /// Returns `None` if no resource could be aquired.
+  fn attempt_aquire(&self,) -> Option<Finish<'_, R, Pol,>> {
+    self.shared.pool.take_resource(Vec::pop,)
+      .or_else(|| self.shared.steal(),)
+      .map(|id,| self.claim(id,),)
+  }
+  /// Returns the resource cached by the current thread to the resource pool.
+  pub fn flush(&self,) {
+    if let Some(id) = self.take_cached() { self.shared.pool.release_resource(id,) }
+  }
+}
+
+impl<R, Pol,> From<MultiResource<R, Pol,>> for ThreadCached<R, Pol,>
+  where R: 'static + Resource,
+    Pol: 'static + PolicyKind<R,>, {
+  #[inline]
+  fn from(from: MultiResource<R, Pol,>,) -> Self { Self::new(from,) }
+}
+
+unsafe impl<R, Pol,> ResourcePool for ThreadCached<R, Pol,>
+  where R: 'static + Resource,
+    Pol: 'static + PolicyKind<R,>, {
+  type Resource = R;
+
+  fn try_get_resource<F,>(&self, f: F,) -> bool
+    where F: FnOnce(usize, &mut Self::Resource,), {
+    match self.take_cached().map(|id,| self.claim(id,),).or_else(|| self.attempt_aquire(),) {
+      Some(finish) => { self.run(finish, f,); true },
+      None => false,
+    }
+  }
+  fn get_resource<P, F,>(&self, f: F,)
+    where P: Park,
+      F: FnOnce(usize, &mut Self::Resource,), {
+    if let Some(id) = self.take_cached() { return self.run(self.claim(id,), f,) }
+
+    let pool = &self.shared.pool;
+
+    //Attempt to aquire a resource, waiting for a resource to be released between attempts.
+    let aquired = pool.queue.wait_handed::<P, _, _, _, _,>(
+      Waiting::DEFAULT, || self.attempt_aquire(),
+      |id,| self.claim(id,),
+      |handed,| pool.pass_on(handed,),
+      pool.pool_id(), Wait::Any(1,),
+    );
+
+    match aquired {
+      Ok(finish) => self.run(finish, f,),
+      Err(_) => unreachable!("the wait cannot be stopped",),
+    }
+  }
+}
+
+#[cfg(test,)]
+mod tests {
+  use super::*;
+  use std::{thread::{self, Thread,}, sync::Barrier,};
+
+  #[test]
+  fn test_thread_cached() {
+    let pool = ThreadCached::new(MultiResource::<usize, Reuse,>::new_resources(2,),);
+    let mut first = 0;
+
+    pool.get_resource::<Thread, _,>(|id, r,| { first = id; *r += 1; },);
+    //The cached resource is reused.
+    for _ in 0..3 {
+      pool.get_resource::<Thread, _,>(|id, r,| { assert_eq!(id, first,); *r += 1; },);
+    }
+    //Another thread steals the cached resource once the pool is empty.
+    assert!(pool.shared.pool.try_get_resource_by_id(1 - first, |_, _,| {
+      thread::scope(|scope,| scope.spawn(|| {
+        pool.get_resource::<Thread, _,>(|id, r,| { assert_eq!(id, first,); *r += 1; },);
+      },).join().unwrap(),);
+    },),);
+    //The stolen resource is no longer cached by this thread.
+    pool.flush();
+    assert!(pool.shared.pool.try_get_resource_by_id(first, |_, r,| assert_eq!(*r, 5,),),);
+  }
+  #[test]
+  fn test_thread_cached_multithread() {
+    const THREADS: usize = 8;
+    const CYCLES: usize = 500;
+
+    let pool = ThreadCached::new(MultiResource::<usize, Reuse,>::new_resources(3,),);
+    let barrier = Barrier::new(THREADS,);
+
+    thread::scope(|scope,| {
+      let handles = (0..THREADS).map(|_,| scope.spawn(|| {
+        barrier.wait();
+        for _ in 0..CYCLES { pool.get_resource::<Thread, _,>(|_, r,| *r += 1,); }
+      },),).collect::<Vec<_>>();
+
+      for handle in handles { handle.join().unwrap(); }
+    },);
+
+    //Every thread flushed its cache when it exited.
+    pool.shared.pool.get_resources::<Thread, _,>(3, |_, resources,| {
+      assert_eq!(resources.iter().map(|r,| **r,).sum::<usize>(), THREADS * CYCLES,);
+    },);
+  }
+}
@@ -0,0 +1,210 @@
+//! Emits `tracing` spans and events describing how threads wait on and hold resources.
+//! 
+//! Spans are emitted for threads waiting on a resource pool and for threads holding
+//! resources. Events are emitted as waiting threads park and are unparked and as
+//! resources are recreated by a reuse pollicy. Every span and event records the type and
+//! address of the resource pool; waiting spans also record its capacity.
+//! 
+//! With the `tracing` feature disabled every function in this module is a no-op.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-18
+
+pub(crate) use self::imp::*;
+
+#[cfg(not(feature = "tracing",),)]
+mod imp {
+  use crate::debug_locks::{PoolId, Wait,};
+
+  /// Exits a span when dropped.
+  pub struct Entered;
+
+  #[inline(always)]
+  pub fn waiting(_: PoolId, _: Wait,) -> Entered { Entered }
+  #[inline(always)]
+  pub fn holding(_: PoolId, _: usize,) -> Entered { Entered }
+  #[inline(always)]
+  pub fn holding_many(_: PoolId, _: &[usize],) -> Entered { Entered }
+  #[inline(always)]
+  pub fn parked(_: PoolId,) {}
+  #[inline(always)]
+  pub fn unparked(_: PoolId,) {}
+  #[inline(always)]
+  pub fn recreated(_: PoolId, _: usize,) {}
+}
+
+#[cfg(feature = "tracing",)]
+mod imp {
+  use crate::debug_locks::{PoolId, Wait,};
+  use tracing::{Level, span::EnteredSpan,};
+
+  /// Exits a span when dropped.
+  pub type Entered = EnteredSpan;
+
+  /// Enters a span covering a thread waiting on a resource pool.
+  /// 
+  /// # Params
+  /// 
+  /// pool --- The resource pool being waited on.  
+  /// wait --- What the thread is waiting for.  
+  #[inline]
+  pub fn waiting(pool: PoolId, wait: Wait,) -> Entered {
+    match wait {
+      Wait::Any(count,) => tracing::span!(
+        Level::TRACE, "waiting",
+        pool = pool.name, address = pool.address, capacity = pool.capacity, count,
+      ),
+      Wait::Id(id,) => tracing::span!(
+        Level::TRACE, "waiting",
+        pool = pool.name, address = pool.address, capacity = pool.capacity, id,
+      ),
+    }.entered()
+  }
+  /// Enters a span covering a thread holding a resource.
+  /// 
+  /// # Params
+  /// 
+  /// pool --- The resource pool the resource belongs to.  
+  /// id --- The Id of the resource.  
+  #[inline]
+  pub fn holding(pool: PoolId, id: usize,) -> Entered {
+    tracing::span!(Level::TRACE, "holding", pool = pool.name, address = pool.address, id,)
+      .entered()
+  }
+  /// Enters a span covering a thread holding several resources.
+  /// 
+  /// # Params
+  /// 
+  /// pool --- The resource pool the resources belong to.  
+  /// ids --- The Ids of the resources.  
+  #[inline]
+  pub fn holding_many(pool: PoolId, ids: &[usize],) -> Entered {
+    tracing::span!(
+      Level::TRACE, "holding", pool = pool.name, address = pool.address, ids = ?ids,
+    ).entered()
+  }
+  /// Records a waiting thread parking.
+  /// 
+  /// # Params
+  /// 
+  /// pool --- The resource pool being waited on.  
+  #[inline]
+  pub fn parked(pool: PoolId,) {
+    tracing::event!(Level::TRACE, pool = pool.name, address = pool.address, "parked",);
+  }
+  /// Records a waiting thread being unparked.
+  /// 
+  /// # Params
+  /// 
+  /// pool --- The resource pool being waited on.  
+  #[inline]
+  pub fn unparked(pool: PoolId,) {
+    tracing::event!(Level::TRACE, pool = pool.name, address = pool.address, "unparked",);
+  }
+  /// Records a resource being recreated by a reuse pollicy.
+  /// 
+  /// # Params
+  /// 
+  /// pool --- The resource pool the resource belongs to.  
+  /// id --- The Id of the resource.  
+  #[inline]
+  pub fn recreated(pool: PoolId, id: usize,) {
+    tracing::event!(
+      Level::DEBUG, pool = pool.name, address = pool.address, id, "resource recreated",
+    );
+  }
+}
+
+#[cfg(all(test, feature = "tracing",),)]
+mod tests {
+  use crate::{*, pollicy::{Reuse, NoReuse,},};
+  use std::{
+    fmt,
+    string::{String, ToString,},
+    sync::{Arc, Mutex,},
+    thread::{self, Thread,},
+    vec::Vec,
+  };
+  use tracing::{
+    Event, Metadata, Subscriber,
+    field::{Field, Visit,},
+    span::{Attributes, Id, Record,},
+  };
+
+  /// Records the names of the spans, the spans entered and exited and the messages of the
+  /// events emitted.
+  #[derive(Clone, Default,)]
+  struct Log(Arc<Mutex<Vec<String>>>,);
+
+  impl Visit for Log {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug,) {
+      if field.name() == "message" { self.0.lock().unwrap().push(std::format!("{:?}", value,),) }
+    }
+  }
+
+  impl Subscriber for Log {
+    fn enabled(&self, _: &Metadata,) -> bool { true }
+    fn new_span(&self, span: &Attributes,) -> Id {
+      let mut log = self.0.lock().unwrap();
+
+      log.push(span.metadata().name().to_string(),);
+      Id::from_u64(log.len() as u64,)
+    }
+    fn record(&self, _: &Id, _: &Record,) {}
+    fn record_follows_from(&self, _: &Id, _: &Id,) {}
+    fn event(&self, event: &Event,) { event.record(&mut self.clone(),) }
+    fn enter(&self, span: &Id,) { self.push_span("enter", span,) }
+    fn exit(&self, span: &Id,) { self.push_span("exit", span,) }
+  }
+
+  impl Log {
+    /// Records a span being entered or exited.
+    fn push_span(&self, action: &str, span: &Id,) {
+      let mut log = self.0.lock().unwrap();
+      let name = log[span.into_u64() as usize - 1].clone();
+
+      log.push(std::format!("{} {}", action, name,),);
+    }
+  }
+
+  #[test]
+  fn test_trace() {
+    let log = Log::default();
+    let resource = Arc::new(SingleResource::<i32, NoReuse,>::new(),);
+
+    tracing::subscriber::with_default(log.clone(), || {
+      resource.get_resource::<Thread, _,>(|_, _,| {},);
+    },);
+    assert_eq!(
+      *log.0.lock().unwrap(),
+      ["resource recreated", "holding", "enter holding", "exit holding",],
+    );
+
+    let resource = Arc::new(SingleResource::<i32, Reuse,>::new(),);
+    let log = Log::default();
+    let mut waiter = None;
+
+    resource.get_resource::<Thread, _,>(|_, _,| {
+      let (resource, subscriber,) = (resource.clone(), log.clone(),);
+
+      waiter = Some(thread::spawn(move || tracing::subscriber::with_default(subscriber, || {
+        resource.get_resource::<Thread, _,>(|_, _,| {},);
+      },),),);
+      while !log.0.lock().unwrap().iter().any(|message,| message == "parked",) {
+        thread::yield_now();
+      }
+    },);
+
+    waiter.unwrap().join().unwrap();
+
+    let log = log.0.lock().unwrap();
+
+    let position = |message,| log.iter().position(|logged,| logged == message,);
+
+    assert_eq!(log.first().map(String::as_str,), Some("waiting",),);
+    assert!(position("unparked",).is_some(),);
+    //The waiting span is exited before the holding span is entered.
+    assert!(position("exit waiting",).unwrap() < position("enter holding",).unwrap(),);
+    assert_eq!(log.last().map(String::as_str,), Some("exit holding",),);
+  }
+}
@@ -0,0 +1,532 @@
+//! Defines the queue of threads waiting on a resource pool and the `CancelToken` used to
+//! stop waiting.
+//! 
+//! Unlike a `SyncStack` a thread joins the queue before checking a resource pool for the
+//! last time so a release can never be missed.
+//! 
+//! Author --- daniel.bechaz@gmail.com  
+//! Last Moddified --- 2026-10-18
+
+use crate::{
+  PoolError, trace,
+  pollicy::Timer,
+  stats::PoolStats,
+  debug_locks::{self, PoolId, Wait,},
+};
+use sync_stack::Park;
+use core::{
+  ptr, mem, hint,
+  time::Duration,
+  cell::{Cell, UnsafeCell,},
+  sync::atomic::{AtomicBool, AtomicUsize, Ordering,},
+};
+
+/// The state of a node waiting to be woken.
+const WAITING: usize = 0;
+/// The state of a node which has been removed from the queue and is being woken.
+const NOTIFIED: usize = 1;
+/// The state of a node which has been woken and is no longer referenced by the queue.
+const DONE: usize = 2;
+
+/// A thread waiting in a `WaitQueue`.
+struct Node {
+  /// The handle used to unpark the thread.
+  handle: *const (),
+  /// Unparks the thread using `handle`.
+  unpark: unsafe fn(*const (),),
+  /// The priority of the thread, higher priorities are woken first.
+  priority: usize,
+  /// The wake state of this node.
+  state: AtomicUsize,
+  /// A value handed to the thread when it was woken.
+  handed: Cell<Option<usize>>,
+  /// The next node in the queue.
+  next: Cell<*const Node>,
+  /// The previous node in the queue.
+  prev: Cell<*const Node>,
+}
+
+impl Node {
+  /// Creates a node which unparks `handle`.
+  /// 
+  /// # Params
+  /// 
+  /// handle --- The handle used to unpark the thread.  
+  /// priority --- The priority of the thread.  
+  fn new<P,>(handle: &P, priority: usize,) -> Self
+    where P: Park, {
+    Self {
+      handle: handle as *const P as *const (),
+      unpark: unpark::<P,>,
+      priority,
+      state: AtomicUsize::new(WAITING,),
+      handed: Cell::new(None,),
+      next: Cell::new(ptr::null(),),
+      prev: Cell::new(ptr::null(),),
+    }
+  }
+  /// Wakes the thread waiting on this node.
+  /// 
+  /// # Safety
+  /// 
+  /// The node must have been notified and removed from the queue.
+  unsafe fn wake(node: *const Node,) {
+    let node = &*node;
+
+    (node.unpark)(node.handle,);
+    //The waiting thread may free the node after this.
+    node.state.store(DONE, Ordering::Release,);
+  }
+}
+
+/// Unparks a thread using a handle of type `P`.
+unsafe fn unpark<P,>(handle: *const (),)
+  where P: Park, { (*(handle as *const P)).unpark() }
+
+/// The nodes in a `WaitQueue`.
+struct List {
+  /// The first node in the queue.
+  head: *const Node,
+  /// The last node in the queue.
+  tail: *const Node,
+}
+
+impl List {
+  /// Adds a node behind every node with the same or a higher priority.
+  unsafe fn push(&mut self, node: *const Node,) {
+    let mut prev = self.tail;
+
+    //Skip the nodes with a lower priority.
+    while !prev.is_null() && (*prev).priority < (*node).priority { prev = (*prev).prev.get() }
+
+    let next = if prev.is_null() { self.head } else { (*prev).next.get() };
+
+    (*node).prev.set(prev,);
+    (*node).next.set(next,);
+
+    if prev.is_null() { self.head = node }
+    else { (*prev).next.set(node,) }
+
+    if next.is_null() { self.tail = node }
+    else { (*next).prev.set(node,) }
+  }
+  /// Unlinks a node from the list.
+  unsafe fn unlink(&mut self, node: *const Node,) {
+    let (prev, next,) = ((*node).prev.get(), (*node).next.get(),);
+
+    if prev.is_null() { self.head = next }
+    else { (*prev).next.set(next,) }
+
+    if next.is_null() { self.tail = prev }
+    else { (*next).prev.set(prev,) }
+  }
+}
+
+/// How a thread waits in a `WaitQueue`.
+#[derive(Clone, Copy,)]
+pub(crate) struct Waiting<'a,> {
+  /// The priority to wait with.
+  pub priority: usize,
+  /// The maximum number of threads which can wait in the queue.
+  pub max: usize,
+  /// Stops the wait once it is cancelled.
+  pub cancel: Option<&'a CancelToken>,
+  /// Stops the wait once the resource pool is closed.
+  pub closed: Option<&'a AtomicBool>,
+  /// Records how long the wait took and if it stopped without claiming the resource pool.
+  pub stats: Option<&'a PoolStats>,
+}
+
+impl Waiting<'_,> {
+  /// Waits with a priority of `0` in an unbounded queue until the resource pool is claimed.
+  pub const DEFAULT: Self = Waiting {
+    priority: 0, max: usize::MAX, cancel: None, closed: None, stats: None,
+  };
+
+  /// Returns the reason to stop waiting, if any.
+  fn stopped(&self,) -> Option<PoolError> {
+    if self.closed.is_some_and(|closed,| closed.load(Ordering::SeqCst,),) {
+      Some(PoolError::Closed)
+    } else if self.cancel.is_some_and(CancelToken::is_cancelled,) {
+      Some(PoolError::Cancelled)
+    } else { None }
+  }
+}
+
+/// A queue of threads waiting on a resource pool.
+/// 
+/// Threads are woken in order of priority and first in first out within a priority.
+pub(crate) struct WaitQueue {
+  /// A flag indicating if `list` is currently locked.
+  locked: AtomicBool,
+  /// The waiting threads.
+  list: UnsafeCell<List>,
+  /// The number of waiting threads.
+  len: AtomicUsize,
+}
+
+unsafe impl Send for WaitQueue {}
+unsafe impl Sync for WaitQueue {}
+
+impl WaitQueue {
+  /// Creates an empty queue.
+  pub const fn new() -> Self {
+    Self {
+      locked: AtomicBool::new(false,),
+      list: UnsafeCell::new(List { head: ptr::null(), tail: ptr::null(), },),
+      len: AtomicUsize::new(0,),
+    }
+  }
+  /// Returns the number of threads waiting in the queue.
+  #[inline]
+  pub fn len(&self,) -> usize { self.len.load(Ordering::Relaxed,) }
+  /// Runs `f` with the list locked.
+  fn with_list<F, T,>(&self, f: F,) -> T
+    where F: FnOnce(&mut List,) -> T, {
+    while self.locked.compare_exchange_weak(
+      false, true, Ordering::Acquire, Ordering::Relaxed,
+    ).is_err() { hint::spin_loop(); }
+
+    let ret = f(unsafe { &mut *self.list.get() },);
+
+    self.locked.store(false, Ordering::Release,);
+
+    ret
+  }
+  /// Wakes the thread at the front of the queue.
+  /// 
+  /// Returns `false` if the queue was empty.
+  #[inline]
+  pub fn pop(&self,) -> bool { self.wake_head(None,) }
+  /// Wakes the thread at the front of the queue handing it `value`.
+  /// 
+  /// Only threads waiting with `wait_handed` can be handed a value.
+  /// 
+  /// Returns `false` if the queue was empty and `value` was not handed over.
+  #[inline]
+  pub fn hand(&self, value: usize,) -> bool { self.wake_head(Some(value,),) }
+  /// Wakes the thread at the front of the queue handing it `value`, if any.
+  /// 
+  /// Returns `false` if the queue was empty.
+  fn wake_head(&self, value: Option<usize>,) -> bool {
+    let node = self.with_list(|list,| {
+      let node = list.head;
+
+      if !node.is_null() {
+        unsafe {
+          list.unlink(node,);
+          //Published to the woken thread by `Node::wake`.
+          (*node).handed.set(value,);
+          (*node).state.store(NOTIFIED, Ordering::Relaxed,);
+        }
+        self.len.fetch_sub(1, Ordering::Relaxed,);
+      }
+
+      node
+    },);
+
+    if node.is_null() { return false }
+
+    unsafe { Node::wake(node,); }
+
+    true
+  }
+  /// Wakes every thread in the queue.
+  pub fn pop_all(&self,) {
+    let mut node = self.with_list(|list,| {
+      let head = list.head;
+      let mut node = head;
+
+      while !node.is_null() {
+        unsafe {
+          (*node).state.store(NOTIFIED, Ordering::Relaxed,);
+          node = (*node).next.get();
+        }
+        self.len.fetch_sub(1, Ordering::Relaxed,);
+      }
+
+      *list = List { head: ptr::null(), tail: ptr::null(), };
+
+      head
+    },);
+
+    while !node.is_null() {
+      unsafe {
+        //The node may be freed once woken.
+        let next = (*node).next.get();
+
+        Node::wake(node,);
+        node = next;
+      }
+    }
+  }
+  /// Adds a node to the queue unless `max` threads are already waiting.
+  /// 
+  /// Returns `false` if the queue was full.
+  fn push(&self, node: &Node, max: usize,) -> bool {
+    self.with_list(|list,| {
+      if self.len() >= max { return false }
+
+      unsafe { list.push(node,); }
+      self.len.fetch_add(1, Ordering::Relaxed,);
+
+      true
+    },)
+  }
+  /// Runs `attempt` until it claims the resource pool, waiting in the queue between
+  /// attempts.
+  /// 
+  /// Returns the claim once this thread has left the queue.
+  /// 
+  /// # Params
+  /// 
+  /// attempt --- Attempts to claim the resource pool.  
+  /// stolen --- Passes on a wake up which was received after `attempt` succeeded.  
+  /// pool --- The resource pool being waited on.  
+  /// wait --- The resources being waited for.  
+  #[inline]
+  pub fn wait<P, C, A, S,>(&self, attempt: A, stolen: S, pool: PoolId, wait: Wait,) -> C
+    where P: Park,
+      A: FnMut() -> Option<C>,
+      S: Fn(), {
+    match self.wait_with::<P, C, A, S,>(Waiting::DEFAULT, attempt, stolen, pool, wait,) {
+      Ok(claim) => claim,
+      Err(_) => unreachable!("the wait cannot be stopped",),
+    }
+  }
+  /// Runs `attempt` until it claims the resource pool, waiting in the queue as described
+  /// by `waiting` between attempts.
+  /// 
+  /// Returns the claim once this thread has left the queue so that releases made while
+  /// the claim is used wake other threads. Returns `Err(QueueFull)` if `waiting.max`
+  /// threads were already waiting when this thread tried to join the queue, `Err(Closed)`
+  /// if `waiting.closed` was set or `Err(Cancelled)` if `waiting.cancel` was cancelled
+  /// before the resource pool was claimed.
+  /// 
+  /// Closing the resource pool or cancelling the token must wake every waiting thread.
+  /// 
+  /// # Params
+  /// 
+  /// waiting --- How to wait in the queue.  
+  /// attempt --- Attempts to claim the resource pool.  
+  /// stolen --- Passes on a wake up which was received after `attempt` succeeded.  
+  /// pool --- The resource pool being waited on.  
+  /// wait --- The resources being waited for.  
+  #[inline]
+  pub fn wait_with<P, C, A, S,>(
+    &self,
+    waiting: Waiting,
+    attempt: A,
+    stolen: S,
+    pool: PoolId,
+    wait: Wait,
+  ) -> Result<C, PoolError>
+    where P: Park,
+      A: FnMut() -> Option<C>,
+      S: Fn(), {
+    self.wait_handed::<P, C, A, _, _,>(
+      waiting, attempt,
+      |_,| unreachable!("nothing is handed to this queue",),
+      |_,| stolen(),
+      pool, wait,
+    )
+  }
+  /// Runs `attempt` until it claims the resource pool or is handed a value by `hand`,
+  /// waiting in the queue as described by `waiting` between attempts.
+  /// 
+  /// Behaves like `wait_with` except that a thread woken by `hand` claims the handed
+  /// value using `handed` instead of competing for it with `attempt`.
+  /// 
+  /// # Params
+  /// 
+  /// waiting --- How to wait in the queue.  
+  /// attempt --- Attempts to claim the resource pool.  
+  /// handed --- Claims a value handed to this thread.  
+  /// stolen --- Passes on a wake up, and the value handed with it, which was received after `attempt` succeeded.  
+  /// pool --- The resource pool being waited on.  
+  /// wait --- The resources being waited for.  
+  pub fn wait_handed<P, C, A, H, S,>(
+    &self,
+    waiting: Waiting,
+    mut attempt: A,
+    handed: H,
+    stolen: S,
+    pool: PoolId,
+    wait: Wait,
+  ) -> Result<C, PoolError>
+    where P: Park,
+      A: FnMut() -> Option<C>,
+      H: FnOnce(usize,) -> C,
+      S: Fn(Option<usize>,), {
+    //Times the wait from when this thread first joins the queue.
+    let mut waited = None::<Timer>;
+    let mut _waiting = None;
+
+    let claimed = 'wait: loop {
+      if let Some(error) = waiting.stopped() { break 'wait Err(error) }
+      if let Some(claim) = attempt() { break 'wait Ok(claim) }
+
+      let handle = P::new();
+      let node = Node::new(&handle, waiting.priority,);
+
+      if !self.push(&node, waiting.max,) { break 'wait Err(PoolError::QueueFull) }
+      if _waiting.is_none() {
+        _waiting = Some(trace::waiting(pool, wait,),);
+        if waiting.stats.is_some_and(PoolStats::has_histograms,) {
+          waited = Some(Timer::start(),);
+        }
+      }
+
+      //Leaves the queue when the loop exits, before the claim is used.
+      let registered = Registered { queue: self, node: &node, stolen: &stolen, };
+      //Wakes this thread if the wait is cancelled.
+      let cancel_node = Node::new(&handle, 0,);
+      let _watching = waiting.cancel.map(|token,| {
+        token.queue.push(&cancel_node, usize::MAX,);
+
+        Watching { queue: &token.queue, node: &cancel_node, }
+      },);
+
+      //Any cancellation or close from this point will wake this thread.
+      if let Some(error) = waiting.stopped() { break 'wait Err(error) }
+
+      //Check for deadlocks before waiting.
+      debug_locks::waiting(pool, wait,);
+
+      //Any release from this point will wake this thread.
+      if let Some(claim) = attempt() { break 'wait Ok(claim) }
+
+      while node.state.load(Ordering::Acquire,) == WAITING {
+        if let Some(error) = waiting.stopped() { break 'wait Err(error) }
+
+        trace::parked(pool,);
+        P::park()
+      }
+      while node.state.load(Ordering::Acquire,) != DONE { hint::spin_loop() }
+      trace::unparked(pool,);
+      //The node has already left the queue.
+      mem::forget(registered,);
+      debug_locks::woken();
+      //The value was handed to this thread ahead of any other thread.
+      if let Some(value) = node.handed.take() {
+        //A stopped wait passes the value on instead.
+        if let Some(error) = waiting.stopped() { stolen(Some(value,),); break 'wait Err(error) }
+
+        break 'wait Ok(handed(value,))
+      }
+    };
+    //End the waiting span before the claim is used so holding does not nest in waiting.
+    _waiting = None;
+
+    if let Some(stats) = waiting.stats {
+      match claimed {
+        Ok(_) => stats.waited(waited.map_or(Duration::from_secs(0,), |timer,| timer.elapsed(),),),
+        Err(error) => stats.failed(error,),
+      }
+    }
+
+    claimed
+  }
+  /// Removes a node from the queue.
+  /// 
+  /// Returns `false` if the node had already been woken.
+  fn remove(&self, node: &Node,) -> bool {
+    let removed = self.with_list(|list,| {
+      if node.state.load(Ordering::Relaxed,) != WAITING { return false }
+
+      unsafe { list.unlink(node,); }
+      self.len.fetch_sub(1, Ordering::Relaxed,);
+
+      true
+    },);
+
+    //Wait until the waking thread is finished with the node.
+    if !removed {
+      while node.state.load(Ordering::Acquire,) != DONE { hint::spin_loop() }
+    }
+
+    removed
+  }
+}
+
+/// A node in the queue of a `CancelToken` which leaves the queue when dropped.
+struct Watching<'a,> {
+  /// The queue the node is in.
+  queue: &'a WaitQueue,
+  /// The node in the queue.
+  node: &'a Node,
+}
+
+impl Drop for Watching<'_,> {
+  fn drop(&mut self,) { self.queue.remove(self.node,); }
+}
+
+/// A node in a `WaitQueue` which leaves the queue when dropped.
+struct Registered<'a, S,>
+  where S: Fn(Option<usize>,), {
+  /// The queue the node is in.
+  queue: &'a WaitQueue,
+  /// The node in the queue.
+  node: &'a Node,
+  /// Passes on a wake up, and any value handed with it, which was received while leaving
+  /// the queue.
+  stolen: &'a S,
+}
+
+impl<S,> Drop for Registered<'_, S,>
+  where S: Fn(Option<usize>,), {
+  fn drop(&mut self,) {
+    debug_locks::woken();
+    if !self.queue.remove(self.node,) { (self.stolen)(self.node.handed.take(),) }
+  }
+}
+
+/// Cancels the aquisitions waiting with it.
+/// 
+/// Cancelling the token wakes every thread waiting with it and makes their aquisitions
+/// return `Err(Cancelled)`. Aquisitions started after the token is cancelled fail
+/// immediately.
+/// 
+/// ```rust
+/// use bottleneck::*;
+/// use std::{thread::{self, Thread}, time::Duration};
+/// 
+/// static RESOURCE: SingleResource<i32> = SingleResource::INIT;
+/// static SHUTDOWN: CancelToken = CancelToken::new();
+/// 
+/// RESOURCE.get_resource::<Thread, _>(|_, _,| {
+///   let waiter = thread::spawn(|| {
+///     RESOURCE.get_resource_cancellable::<Thread, _>(&SHUTDOWN, |_, _,| {})
+///   });
+/// 
+///   thread::sleep(Duration::from_millis(100));
+///   SHUTDOWN.cancel();
+///   assert_eq!(waiter.join().unwrap(), Err(PoolError::Cancelled));
+/// });
+/// ```
+pub struct CancelToken {
+  /// A flag indicating if the token has been cancelled.
+  cancelled: AtomicBool,
+  /// The threads waiting with the token.
+  queue: WaitQueue,
+}
+
+impl CancelToken {
+  /// Creates a token which has not been cancelled.
+  pub const fn new() -> Self {
+    Self { cancelled: AtomicBool::new(false,), queue: WaitQueue::new(), }
+  }
+  /// Returns `true` if the token has been cancelled.
+  #[inline]
+  pub fn is_cancelled(&self,) -> bool { self.cancelled.load(Ordering::SeqCst,) }
+  /// Cancels the token and wakes every thread waiting with it.
+  pub fn cancel(&self,) {
+    self.cancelled.store(true, Ordering::SeqCst,);
+    self.queue.pop_all();
+  }
+}
+
+impl Default for CancelToken {
+  #[inline]
+  fn default() -> Self { Self::new() }
+}